@@ -1,15 +1,6 @@
 extern crate matrix;
+use matrix::Matrix;
 use matrix::linalg::optimisation::{quasi_newton_min, dowhill_simplex};
-use std::io::BufRead;
-
-fn parse_string(string_to_parse: &str, split_delimiters: Vec<char>) -> Vec<f64> {
-    let mut result = Vec::new();
-    for entry in string_to_parse.split(&split_delimiters[..]).filter(|&x| !x.is_empty()) {
-        let number = entry.parse::<f64>().expect(&format!("Could not interpret '{entry}' as an f64"));
-        result.push(number);
-    }
-    return result;
-}
 
 fn main() -> std::io::Result<()> {
     let acc = 0.01;
@@ -47,14 +38,7 @@ fn main() -> std::io::Result<()> {
 
 
     // read data from standard input stream
-    let mut data: Vec<Vec<f64>> = Vec::new();
-    let mut lines = std::io::stdin().lock().lines();
-    while let Some(line) = lines.next() {
-        let line = line?;
-        if line.len() == 0 {break}
-        if line.chars().nth(0).unwrap() == '#' {continue}
-        data.push(parse_string(&line, vec![' ', '\t']));
-    }
+    let data = Matrix::read_table(std::io::stdin().lock()).unwrap_or_else(|err| panic!("Could not read data: {}", err));
 
     let breit_wigner = |e: f64, variables: &Vec<f64>| -> f64 {
         let (m, gamma, a) = (variables[0], variables[1], variables[2]);
@@ -63,8 +47,8 @@ fn main() -> std::io::Result<()> {
     
     let cost = |variables: &Vec<f64>| -> f64 {
         let mut result = 0.0;
-        for point in data.iter() {
-            let (e, sigma, delta) = (point[0], point[1], point[2]);
+        for i in 0..data.num_rows {
+            let (e, sigma, delta) = (data[0][i], data[1][i], data[2][i]);
             result += (breit_wigner(e, variables) - sigma).powi(2) / (delta * delta);
         }
         return result
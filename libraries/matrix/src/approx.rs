@@ -0,0 +1,148 @@
+use super::{Matrix, AsMatrixView, SMatrix};
+use num_complex::Complex;
+
+// Approximate equality of floating point numbers and of matrices element by element.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    // |a - b| <= tol
+    Absolute(f64),
+    // |a - b| <= tol * max(|a|, |b|)
+    Relative(f64),
+    // at most this many representable f64 values between a and b
+    Ulps(u64),
+}
+
+impl From<f64> for Tolerance {
+    fn from(tol: f64) -> Self {
+        Tolerance::Absolute(tol)
+    }
+}
+
+fn ordered_bits(x: f64) -> i128 {
+    // maps f64 monotonically onto the integers, with -0 and +0 both at 0
+    let bits = x.to_bits() as i64 as i128;
+    if bits < 0 {i64::MIN as i128 - bits} else {bits}
+}
+
+impl Tolerance {
+    pub fn error(&self, a: f64, b: f64) -> f64 {
+        // the quantity compared against the tolerance, NaN if either number is NaN
+        if a == b {return 0.0}
+        match self {
+            Tolerance::Absolute(_) => (a - b).abs(),
+            Tolerance::Relative(_) => (a - b).abs() / a.abs().max(b.abs()),
+            Tolerance::Ulps(_) => if a.is_nan() || b.is_nan() {f64::NAN} else {(ordered_bits(a) - ordered_bits(b)).abs() as f64},
+        }
+    }
+
+    pub fn accepts(&self, error: f64) -> bool {
+        match *self {
+            Tolerance::Absolute(tol) | Tolerance::Relative(tol) => error <= tol,
+            Tolerance::Ulps(tol) => error <= tol as f64,
+        }
+    }
+}
+
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, tol: Tolerance) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, tol: Tolerance) -> bool {
+        tol.accepts(tol.error(*self, *other))
+    }
+}
+
+impl ApproxEq for f32 {
+    fn approx_eq(&self, other: &Self, tol: Tolerance) -> bool {
+        // ULPs are counted in f64, so an f32 ULP is 2^29 of them
+        let tol = match tol {Tolerance::Ulps(n) => Tolerance::Ulps(n << 29), tol => tol};
+        (*self as f64).approx_eq(&(*other as f64), tol)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Complex<T> {
+    fn approx_eq(&self, other: &Self, tol: Tolerance) -> bool {
+        // real and imaginary parts separately
+        self.re.approx_eq(&other.re, tol) && self.im.approx_eq(&other.im, tol)
+    }
+}
+
+impl<T: ApproxEq + Copy> ApproxEq for Matrix<T> {
+    fn approx_eq(&self, other: &Self, tol: Tolerance) -> bool {
+        self.num_rows == other.num_rows && self.num_cols == other.num_cols
+            && self.data().iter().zip(other.data().iter()).all(|(a, b)| a.approx_eq(b, tol))
+    }
+}
+
+impl<T: ApproxEq + Copy, const R: usize, const C: usize> ApproxEq for SMatrix<T, R, C> {
+    fn approx_eq(&self, other: &Self, tol: Tolerance) -> bool {
+        self.iter().zip(other.iter()).all(|(a, b)| a.approx_eq(b, tol))
+    }
+}
+
+#[doc(hidden)]
+pub fn matrix_close_message(left: &impl AsMatrixView<f64>, right: &impl AsMatrixView<f64>, tol: Tolerance) -> Option<String> {
+    // None if the matrices are close, otherwise a description of the worst element for assert_matrix_close!
+    let (left, right) = (left.as_view(), right.as_view());
+    if left.num_rows != right.num_rows || left.num_cols != right.num_cols {
+        return Some(format!("shapes differ: {} x {} and {} x {}", left.num_rows, left.num_cols, right.num_rows, right.num_cols))
+    }
+    let mut worst: Option<(usize, usize, f64)> = None;
+    for j in 0..left.num_cols {
+        for i in 0..left.num_rows {
+            // NaN counts as the largest error
+            let error = tol.error(left[(i, j)], right[(i, j)]);
+            if tol.accepts(error) {continue}
+            let rank = |e: f64| if e.is_nan() {f64::INFINITY} else {e};
+            if worst.map_or(true, |(_, _, max)| rank(error) > rank(max)) {worst = Some((i, j, error))}
+        }
+    }
+    worst.map(|(i, j, error)| format!(
+        "element ({i}, {j}) is off by {error:e} with {tol:?}: left = {:e}, right = {:e}", left[(i, j)], right[(i, j)]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalars() {
+        assert!(1f64.approx_eq(&(1.0 + 1e-10), Tolerance::Absolute(1e-9)));
+        assert!(!1e-3f64.approx_eq(&2e-3, Tolerance::Relative(0.1)));
+        assert!(1e20f64.approx_eq(&(1e20 + 1e5), Tolerance::Relative(1e-14)));
+        assert!(1f64.approx_eq(&(1.0 + f64::EPSILON), Tolerance::Ulps(1)));
+        assert!(!1f64.approx_eq(&(1.0 + 2.0 * f64::EPSILON), Tolerance::Ulps(1)));
+        assert!((-0f64).approx_eq(&f64::from_bits(1), Tolerance::Ulps(1)));
+        assert!(!f64::NAN.approx_eq(&f64::NAN, Tolerance::Absolute(1.0)));
+        assert!(1f32.approx_eq(&(1.0 + f32::EPSILON), Tolerance::Ulps(1)));
+    }
+
+    #[test]
+    fn test_matrices() {
+        let a = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        let mut b = &a + Matrix::ones(2, 2) * 1e-12;
+        assert!(a.approx_eq(&b, Tolerance::Absolute(1e-11)));
+        assert_eq!(matrix_close_message(&a, &b, Tolerance::Relative(1e-11)), None);
+        assert_matrix_close!(a, b, 1e-11);
+
+        b[1][0] = 3.5;
+        b[0][1] = 2.25;
+        assert!(!a.approx_eq(&b, Tolerance::Absolute(1e-11)));
+        assert_eq!(
+            matrix_close_message(&a, &b, Tolerance::Absolute(1e-11)).unwrap(),
+            "element (0, 1) is off by 5e-1 with Absolute(1e-11): left = 3e0, right = 3.5e0"
+        );
+        assert!(!a.approx_eq(&Matrix::ones(2, 3), Tolerance::Absolute(1.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "element (1, 1)")]
+    fn test_assert_macro() {
+        let a = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        let b = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.1]]);
+        assert_matrix_close!(a, b, Tolerance::Relative(1e-3));
+    }
+}
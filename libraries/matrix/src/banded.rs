@@ -0,0 +1,353 @@
+use super::{Matrix, MatrixError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TridiagonalMatrix {
+    // elements A(row, column)
+    pub lower: Vec<f64>,  // A(i+1, i), length n-1
+    pub diag: Vec<f64>,  // A(i, i), length n
+    pub upper: Vec<f64>,  // A(i, i+1), length n-1
+}
+
+impl TridiagonalMatrix {
+    pub fn new(lower: Vec<f64>, diag: Vec<f64>, upper: Vec<f64>) -> Self {
+        assert!(lower.len() + 1 == diag.len() && upper.len() + 1 == diag.len(), "Non-compatible dimensions!");
+        Self {lower: lower, diag: diag, upper: upper}
+    }
+
+    pub fn dim(&self) -> usize {
+        self.diag.len()
+    }
+
+    pub fn to_dense(&self) -> Matrix<f64> {
+        let n = self.dim();
+        let mut result = Matrix::zeros(n, n);
+        for i in 0..n {
+            result[i][i] = self.diag[i];
+            if i + 1 < n {
+                result[i][i+1] = self.lower[i];
+                result[i+1][i] = self.upper[i];
+            }
+        }
+        return result
+    }
+
+    pub fn mul_vec(&self, x: &Vec<f64>) -> Vec<f64> {
+        let n = self.dim();
+        assert!(x.len() == n, "Non-compatible dimensions!");
+        (0..n).map(|i| {
+            let mut sum = self.diag[i] * x[i];
+            if i > 0 {sum += self.lower[i-1] * x[i-1]}
+            if i + 1 < n {sum += self.upper[i] * x[i+1]}
+            sum
+        }).collect()
+    }
+
+    pub fn solve(&self, b: &mut Vec<f64>) -> Result<(), MatrixError> {
+        // b <- A^-1 b by Gaussian elimination with partial pivoting, O(n)
+        // fails with Singular on a vanishing k'th pivot and with Empty for n == 0
+        let n = self.dim();
+        assert!(b.len() == n, "Non-compatible dimensions!");
+        if n == 0 {
+            return Err(MatrixError::Empty)
+        }
+        // row k of U has entries u0 (diagonal), u1 and u2 (fill-in from pivoting)
+        let (mut u0, mut u1, mut u2) = (vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+        let mut row = [self.diag[0], if n > 1 {self.upper[0]} else {0.0}, 0.0];
+        for k in 0..n {
+            if k + 1 == n {
+                if row[0] == 0.0 {return Err(MatrixError::Singular {pivot: k})}
+                u0[k] = row[0];
+                break
+            }
+            let mut next = [self.lower[k], self.diag[k+1], if k + 2 < n {self.upper[k+1]} else {0.0}];
+            if next[0].abs() > row[0].abs() {
+                std::mem::swap(&mut row, &mut next);
+                b.swap(k, k+1);
+            }
+            if row[0] == 0.0 {return Err(MatrixError::Singular {pivot: k})}
+            let m = next[0] / row[0];
+            (u0[k], u1[k], u2[k]) = (row[0], row[1], row[2]);
+            b[k+1] -= m * b[k];
+            row = [next[1] - m * row[1], next[2] - m * row[2], 0.0];
+        }
+        for k in (0..n).rev() {
+            let mut sum = b[k];
+            if k + 1 < n {sum -= u1[k] * b[k+1]}
+            if k + 2 < n {sum -= u2[k] * b[k+2]}
+            b[k] = sum / u0[k];
+        }
+        return Ok(())
+    }
+
+    pub fn determinant(&self) -> f64 {
+        // three-term recurrence of the leading principal minors, the 0 x 0 determinant is 1
+        if self.dim() == 0 {return 1.0}
+        let (mut prev, mut det) = (1.0, self.diag[0]);
+        for i in 1..self.dim() {
+            (prev, det) = (det, self.diag[i] * det - self.lower[i-1] * self.upper[i-1] * prev);
+        }
+        return det
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CyclicTridiagonalMatrix {
+    // tridiagonal matrix with the periodic corner elements A(0, n-1) and A(n-1, 0)
+    pub tridiagonal: TridiagonalMatrix,
+    pub top_right: f64,
+    pub bottom_left: f64,
+}
+
+impl CyclicTridiagonalMatrix {
+    pub fn new(lower: Vec<f64>, diag: Vec<f64>, upper: Vec<f64>, top_right: f64, bottom_left: f64) -> Self {
+        assert!(diag.len() >= 3, "Cyclic system must be at least 3 x 3");
+        Self {tridiagonal: TridiagonalMatrix::new(lower, diag, upper), top_right: top_right, bottom_left: bottom_left}
+    }
+
+    pub fn dim(&self) -> usize {
+        self.tridiagonal.dim()
+    }
+
+    pub fn to_dense(&self) -> Matrix<f64> {
+        let mut result = self.tridiagonal.to_dense();
+        let n = self.dim();
+        result[n-1][0] = self.top_right;
+        result[0][n-1] = self.bottom_left;
+        return result
+    }
+
+    pub fn mul_vec(&self, x: &Vec<f64>) -> Vec<f64> {
+        let n = self.dim();
+        let mut y = self.tridiagonal.mul_vec(x);
+        y[0] += self.top_right * x[n-1];
+        y[n-1] += self.bottom_left * x[0];
+        return y
+    }
+
+    pub fn solve(&self, b: &mut Vec<f64>) -> Result<(), MatrixError> {
+        // Sherman-Morrison: A = T + u v^T with u = (gamma, 0, ..., bottom_left), v = (1, 0, ..., top_right / gamma)
+        let n = self.dim();
+        let gamma = if self.tridiagonal.diag[0] == 0.0 {1.0} else {-self.tridiagonal.diag[0]};
+        let mut t = self.tridiagonal.clone();
+        t.diag[0] -= gamma;
+        t.diag[n-1] -= self.bottom_left * self.top_right / gamma;
+
+        let mut z = vec![0.0; n];
+        z[0] = gamma;
+        z[n-1] = self.bottom_left;
+        t.solve(b)?;
+        t.solve(&mut z)?;
+        let v_dot = |x: &Vec<f64>| x[0] + self.top_right / gamma * x[n-1];
+        let factor = v_dot(b) / (1.0 + v_dot(&z));
+        for (bi, zi) in b.iter_mut().zip(z.iter()) {
+            *bi -= factor * zi;
+        }
+        return Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedMatrix {
+    // column-major band storage, A(i, j) with j - ku <= i <= j + kl
+    // lives at data[j * (kl + ku + 1) + ku + i - j]
+    data: Vec<f64>,
+    pub dim: usize,
+    pub kl: usize,
+    pub ku: usize,
+}
+
+impl BandedMatrix {
+    pub fn zeros(dim: usize, kl: usize, ku: usize) -> Self {
+        Self {data: vec![0.0; dim * (kl + ku + 1)], dim: dim, kl: kl, ku: ku}
+    }
+
+    fn in_band(&self, i: usize, j: usize) -> bool {
+        i + self.ku >= j && i <= j + self.kl
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        if !self.in_band(i, j) {return 0.0}
+        self.data[j * (self.kl + self.ku + 1) + self.ku + i - j]
+    }
+
+    pub fn set(&mut self, val: f64, i: usize, j: usize) {
+        assert!(self.in_band(i, j), "Index outside of band");
+        self.data[j * (self.kl + self.ku + 1) + self.ku + i - j] = val;
+    }
+
+    pub fn from_dense(mat: &Matrix<f64>, kl: usize, ku: usize) -> Self {
+        // elements outside the band are ignored
+        assert!(mat.num_rows == mat.num_cols, "Matrix is not square");
+        let mut result = Self::zeros(mat.num_rows, kl, ku);
+        for j in 0..mat.num_cols {
+            for i in j.saturating_sub(ku)..usize::min(mat.num_rows, j + kl + 1) {
+                result.set(mat[j][i], i, j);
+            }
+        }
+        return result
+    }
+
+    pub fn to_dense(&self) -> Matrix<f64> {
+        let mut result = Matrix::zeros(self.dim, self.dim);
+        for j in 0..self.dim {
+            for i in j.saturating_sub(self.ku)..usize::min(self.dim, j + self.kl + 1) {
+                result[j][i] = self.get(i, j);
+            }
+        }
+        return result
+    }
+
+    pub fn mul_vec(&self, x: &Vec<f64>) -> Vec<f64> {
+        assert!(x.len() == self.dim, "Non-compatible dimensions!");
+        let mut y = vec![0.0; self.dim];
+        for j in 0..self.dim {
+            for i in j.saturating_sub(self.ku)..usize::min(self.dim, j + self.kl + 1) {
+                y[i] += self.get(i, j) * x[j];
+            }
+        }
+        return y
+    }
+
+    fn factor(&self) -> Result<(Vec<f64>, Vec<usize>), MatrixError> {
+        // band LU with partial pivoting in row-major storage with room for kl extra upper diagonals
+        // W[i][j] lives at w[i * width + kl + j - i], multipliers are kept below the diagonal
+        let (n, kl, ku) = (self.dim, self.kl, self.ku);
+        let width = 2 * kl + ku + 1;
+        let idx = |i: usize, j: usize| i * width + kl + j - i;
+        let mut w = vec![0.0; n * width];
+        for j in 0..n {
+            for i in j.saturating_sub(ku)..usize::min(n, j + kl + 1) {
+                w[idx(i, j)] = self.get(i, j);
+            }
+        }
+
+        let mut pivots = Vec::with_capacity(n);
+        for k in 0..n {
+            let last_row = usize::min(n - 1, k + kl);
+            let last_col = usize::min(n - 1, k + ku + kl);
+            let mut p = k;
+            for i in k+1..=last_row {
+                if w[idx(i, k)].abs() > w[idx(p, k)].abs() {p = i}
+            }
+            if w[idx(p, k)] == 0.0 {return Err(MatrixError::Singular {pivot: k})}
+            pivots.push(p);
+            if p != k {
+                for j in k..=last_col {
+                    w.swap(idx(k, j), idx(p, j));
+                }
+            }
+            for i in k+1..=last_row {
+                let m = w[idx(i, k)] / w[idx(k, k)];
+                w[idx(i, k)] = m;
+                if m == 0.0 {continue}
+                for j in k+1..=last_col {
+                    w[idx(i, j)] -= m * w[idx(k, j)];
+                }
+            }
+        }
+        return Ok((w, pivots))
+    }
+
+    pub fn solve(&self, b: &mut Vec<f64>) -> Result<(), MatrixError> {
+        // b <- A^-1 b by band LU with partial pivoting, O(n kl (kl + ku))
+        // fails with Singular on a vanishing k'th pivot
+        let (n, kl, ku) = (self.dim, self.kl, self.ku);
+        assert!(b.len() == n, "Non-compatible dimensions!");
+        let (w, pivots) = self.factor()?;
+        let width = 2 * kl + ku + 1;
+        let idx = |i: usize, j: usize| i * width + kl + j - i;
+        for k in 0..n {  // apply P_k and L_k in sequence
+            b.swap(k, pivots[k]);
+            for i in k+1..usize::min(n, k + kl + 1) {
+                b[i] -= w[idx(i, k)] * b[k];
+            }
+        }
+        for k in (0..n).rev() {  // back substitution with U
+            let mut sum = b[k];
+            for j in k+1..usize::min(n, k + ku + kl + 1) {
+                sum -= w[idx(k, j)] * b[j];
+            }
+            b[k] = sum / w[idx(k, k)];
+        }
+        return Ok(())
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let (n, kl, ku) = (self.dim, self.kl, self.ku);
+        let (w, pivots) = match self.factor() {
+            Ok(result) => result,
+            Err(_) => return 0.0,
+        };
+        let width = 2 * kl + ku + 1;
+        let mut det = 1.0;
+        for k in 0..n {
+            det *= w[k * width + kl];
+            if pivots[k] != k {det = -det}
+        }
+        return det
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::zip;
+
+    fn is_close(a: &Vec<f64>, b: &Vec<f64>, acc: f64) -> bool {
+        zip(a, b).fold(true, |acc_, (x, y)| acc_ && (x - y).abs() < acc)
+    }
+
+    #[test]
+    fn test_tridiagonal() {
+        let t = TridiagonalMatrix::new(vec![3.0, 1.0, -2.0], vec![0.0, 4.0, 1.0, 2.0], vec![1.0, 2.0, 5.0]);
+        let x = vec![1.0, -1.0, 2.0, 0.5];
+        let mut b = t.mul_vec(&x);
+        assert!(is_close(&b, &(t.to_dense() * Matrix::from_data(x.clone(), 4, 1)).data(), 1e-15));
+        t.solve(&mut b).unwrap();  // zero first pivot requires pivoting
+        assert!(is_close(&b, &x, 1e-14));
+
+        let mut lu = t.to_dense();
+        let perm = super::super::linalg::lu::decomp(&mut lu).unwrap();
+        assert!((t.determinant() - super::super::linalg::lu::determinant(&lu, &perm)).abs() < 1e-12);
+
+        let singular = TridiagonalMatrix::new(vec![0.0], vec![1.0, 0.0], vec![1.0]);
+        assert_eq!(singular.solve(&mut vec![1.0, 1.0]), Err(MatrixError::Singular {pivot: 1}));
+
+        let empty = TridiagonalMatrix {lower: vec![], diag: vec![], upper: vec![]};
+        assert_eq!(empty.solve(&mut vec![]), Err(MatrixError::Empty));
+        assert_eq!(empty.determinant(), 1.0);
+    }
+
+    #[test]
+    fn test_cyclic() {
+        let c = CyclicTridiagonalMatrix::new(vec![-1.0; 4], vec![3.0; 5], vec![-1.0; 4], -1.0, 0.5);
+        let x = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let mut b = c.mul_vec(&x);
+        assert!(is_close(&b, &(c.to_dense() * Matrix::from_data(x.clone(), 5, 1)).data(), 1e-15));
+        c.solve(&mut b).unwrap();
+        assert!(is_close(&b, &x, 1e-14));
+    }
+
+    #[test]
+    fn test_banded() {
+        let n = 8;
+        let mut a = BandedMatrix::zeros(n, 2, 1);
+        for j in 0..n {
+            for i in j.saturating_sub(1)..usize::min(n, j + 3) {
+                a.set(((3 * i + 5 * j) % 7) as f64 - 3.0, i, j);
+            }
+        }
+        let dense = a.to_dense();
+        assert_eq!(BandedMatrix::from_dense(&dense, 2, 1), a);
+
+        let x: Vec<f64> = (0..n).map(|i| i as f64 - 2.5).collect();
+        let mut b = a.mul_vec(&x);
+        a.solve(&mut b).unwrap();
+        assert!(is_close(&b, &x, 1e-12));
+
+        let mut lu = dense.clone();
+        let perm = super::super::linalg::lu::decomp(&mut lu).unwrap();
+        let det = super::super::linalg::lu::determinant(&lu, &perm);
+        assert!((a.determinant() - det).abs() < 1e-12 * det.abs());
+    }
+}
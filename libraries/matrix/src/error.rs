@@ -0,0 +1,82 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatrixError {
+    // shapes are (num_rows, num_cols)
+    DimensionMismatch {operation: &'static str, lhs: (usize, usize), rhs: (usize, usize)},
+    NotSquare {shape: (usize, usize)},
+    Empty,
+    Singular {pivot: usize},
+    NotPositiveDefinite {pivot: usize},
+    NoConvergence {index: usize},
+    NoRealSolution {operation: &'static str},
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::DimensionMismatch {operation, lhs, rhs} => write!(
+                f, "Non-compatible dimensions! {operation}: {} x {} and {} x {}", lhs.0, lhs.1, rhs.0, rhs.1
+            ),
+            MatrixError::NotSquare {shape} => write!(f, "Matrix is not square: {} x {}", shape.0, shape.1),
+            MatrixError::Empty => write!(f, "Matrix has no elements"),
+            MatrixError::Singular {pivot} => write!(f, "Matrix is singular: pivot {pivot} vanishes"),
+            MatrixError::NotPositiveDefinite {pivot} => write!(f, "Matrix is not positive definite: pivot {pivot} is not positive"),
+            MatrixError::NoConvergence {index} => write!(f, "Iteration did not converge at index {index}"),
+            MatrixError::NoRealSolution {operation} => write!(f, "No real solution of {operation} exists"),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(std::io::Error),
+    // line numbers start at 1
+    Parse {line: usize, message: String},
+    // malformed binary input
+    Format(String),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "{err}"),
+            ReadError::Parse {line, message} => write!(f, "line {line}: {message}"),
+            ReadError::Format(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Parse {..} | ReadError::Format(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(err: std::io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+pub(crate) fn check_square(num_rows: usize, num_cols: usize) -> Result<(), MatrixError> {
+    if num_rows != num_cols {return Err(MatrixError::NotSquare {shape: (num_rows, num_cols)})}
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = MatrixError::DimensionMismatch {operation: "mat_mul", lhs: (2, 3), rhs: (2, 3)};
+        assert_eq!(format!("{err}"), "Non-compatible dimensions! mat_mul: 2 x 3 and 2 x 3");
+        assert_eq!(check_square(2, 3), Err(MatrixError::NotSquare {shape: (2, 3)}));
+    }
+}
@@ -1,3 +1,23 @@
+extern crate num_complex;
+extern crate num_traits;
+
+#[macro_use]
+mod macros;
+
+mod error;
+pub use error::{MatrixError, ReadError};
 mod matrix;
-pub use matrix::Matrix;
+pub use matrix::{Matrix, MatrixView, MatrixViewMut, AsMatrixView, AsMatrixViewMut, RandomSource};
+mod sparse;
+pub use sparse::{CooMatrix, CsrMatrix, CscMatrix};
+mod banded;
+pub use banded::{TridiagonalMatrix, CyclicTridiagonalMatrix, BandedMatrix};
+mod small;
+pub use small::{SMatrix, SVector};
+mod approx;
+pub use approx::{ApproxEq, Tolerance};
+#[doc(hidden)]
+pub use approx::matrix_close_message;
+mod npy;
+pub use npy::{NpyElement, NpzReader, NpzWriter};
 pub mod linalg;
\ No newline at end of file
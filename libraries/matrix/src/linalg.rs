@@ -1,24 +1,37 @@
-use super::Matrix;
+use super::{Matrix, AsMatrixView, AsMatrixViewMut, MatrixError};
+use super::error::check_square;
+use num_complex::ComplexFloat;
 
+pub mod cholesky;
+pub mod complex;
 pub mod eig;
+pub mod eigs;
+pub mod iterative;
+pub mod ldl;
 pub mod lstsq;
+pub mod lu;
+pub mod matfun;
 pub mod qr;
+pub mod schur;
+pub mod svd;
+pub mod tridiagonal;
 pub mod optimisation;
 
-pub fn back_substitution(r: &Matrix<f64>, b: &mut Matrix<f64>) {
-    for i in (0..b.num_rows).rev() {
-        let mut sum = 0.0;
-        for j in i+1..b.num_rows {
-            sum += r[j][i] * b[0][j];
+pub fn back_substitution<T: ComplexFloat<Real = f64>>(r: &impl AsMatrixView<T>, b: &mut impl AsMatrixViewMut<T>) {
+    // b <- R^-1 b for every column of b, only the upper triangle of R is read
+    let r = r.as_view();
+    let mut b = b.as_view_mut();
+    for k in 0..b.num_cols {
+        for i in (0..b.num_rows).rev() {
+            let mut sum = T::zero();
+            for j in i+1..b.num_rows {
+                sum = sum + r[(i, j)] * b[(j, k)];
+            }
+            b[(i, k)] = (b[(i, k)] - sum) / r[(i, i)];
         }
-        b[0][i] = (b[0][i] - sum) / r[i][i];
     }
 }
 
-/* pub fn gauss_elemination(a: &mut Matrix<f64>) {
-    pseudo code at: https://en.wikipedia.org/wiki/Gaussian_elimination
-} */
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,102 @@
+use super::{Matrix, AsMatrixViewMut, MatrixError, check_square};
+
+pub fn decomp(a: &mut impl AsMatrixViewMut<f64>) -> Result<(), MatrixError> {
+    // A <- L such that A = L L^T, only the lower triangle of A is read
+    // fails with NotPositiveDefinite if the j'th pivot is not positive, i.e. A is not positive definite
+    let mut a = a.as_view_mut();
+    let n = a.num_rows;
+    check_square(n, a.num_cols)?;
+    for j in 0..n {
+        let mut d = a[(j, j)];
+        for k in 0..j {
+            d -= a[(j, k)] * a[(j, k)];
+        }
+        if !(d > 0.0) {
+            return Err(MatrixError::NotPositiveDefinite {pivot: j})
+        }
+        let ljj = d.sqrt();
+        a[(j, j)] = ljj;
+        for i in j+1..n {
+            let mut sum = a[(i, j)];
+            for k in 0..j {
+                sum -= a[(i, k)] * a[(j, k)];
+            }
+            a[(i, j)] = sum / ljj;
+        }
+        for i in 0..j {  // clear the upper triangle
+            a[(i, j)] = 0.0;
+        }
+    }
+    return Ok(())
+}
+
+pub fn solve(l: &Matrix<f64>, b: &mut Matrix<f64>) {
+    // solves L L^T x = b for every column of b, b <- x
+    let n = l.num_rows;
+    assert!(b.num_rows == n, "Non-compatible dimensions!");
+    for k in 0..b.num_cols {
+        let x = &mut b[k];
+        for i in 0..n {  // L y = b
+            let mut sum = x[i];
+            for j in 0..i {
+                sum -= l[j][i] * x[j];
+            }
+            x[i] = sum / l[i][i];
+        }
+        for i in (0..n).rev() {  // L^T x = y, row i of L^T is column i of L
+            let mut sum = x[i];
+            for j in i+1..n {
+                sum -= l[i][j] * x[j];
+            }
+            x[i] = sum / l[i][i];
+        }
+    }
+}
+
+pub fn log_determinant(l: &Matrix<f64>) -> f64 {
+    2.0 * (0..l.num_rows).map(|i| l[i][i].ln()).sum::<f64>()
+}
+
+pub fn inverse(l: &Matrix<f64>) -> Matrix<f64> {
+    let mut result = Matrix::idty(l.num_rows);
+    solve(l, &mut result);
+    return result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::zip;
+
+    #[test]
+    fn test_decomp() {
+        let mut a = Matrix::new(vec![vec![4.0, 12.0, -16.0], vec![12.0, 37.0, -43.0], vec![-16.0, -43.0, 98.0]]);
+        decomp(&mut a).unwrap();
+
+        assert!(
+            zip(
+                a.iter(),
+                [2.0, 6.0, -8.0, 0.0, 1.0, 5.0, 0.0, 0.0, 3.0]
+            ).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-15))
+        );
+        assert!((log_determinant(&a) - 36.0_f64.ln()).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = Matrix::new(vec![vec![4.0, 12.0, -16.0], vec![12.0, 37.0, -43.0], vec![-16.0, -43.0, 98.0]]);
+        let mut l = a.clone();
+        decomp(&mut l).unwrap();
+        let a_inv = inverse(&l);
+
+        assert!(
+            (&a * &a_inv - Matrix::idty(3)).iter().fold(true, |acc, item| acc && item.abs() < 1e-12)
+        );
+    }
+
+    #[test]
+    fn test_not_positive_definite() {
+        let mut a = Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 1.0]]);
+        assert_eq!(decomp(&mut a), Err(MatrixError::NotPositiveDefinite {pivot: 1}));
+    }
+}
@@ -0,0 +1,147 @@
+use super::{Matrix, MatrixError, check_square, back_substitution};
+use super::eig::MAX_SWEEPS;
+use num_complex::Complex;
+
+// Counterparts of the real routines for Matrix<Complex<f64>>, where transposes become conjugate transposes.
+// lu and back_substitution are generic and work on complex matrices directly.
+
+type C = Complex<f64>;
+const ZERO: C = Complex {re: 0.0, im: 0.0};
+
+fn dot(x: &[C], y: &[C]) -> C {
+    // <x, y> = x^H y
+    x.iter().zip(y.iter()).fold(ZERO, |sum, (a, b)| sum + a.conj() * b)
+}
+
+pub fn qr_decomp(mat: &mut Matrix<C>, r: &mut Matrix<C>) {
+    // modified Gram-Schmidt, A <- Q with orthonormal columns and R upper triangular such that A = Q R
+    let m = mat.num_cols;
+    for i in 0..m {
+        let norm = dot(&mat[i], &mat[i]).re.sqrt();
+        r[i][i] = Complex::new(norm, 0.0);
+        for x in mat[i].iter_mut() {*x /= norm}
+        for j in i+1..m {
+            let inner_prod = dot(&mat[i], &mat[j]);
+            r[j][i] = inner_prod;
+            for k in 0..mat.num_rows {
+                let qk = mat[i][k];
+                mat[j][k] -= inner_prod * qk;
+            }
+        }
+    }
+}
+
+pub fn qr_solve(q: &Matrix<C>, r: &Matrix<C>, b: &Matrix<C>) -> Matrix<C> {
+    // least squares solution of A x = b for a single column b
+    let mut x = q.conj_transpose() * b;
+    back_substitution(r, &mut x);
+    return x
+}
+
+pub fn jacobi_hermitian(a: &mut Matrix<C>) -> Result<(Vec<f64>, Matrix<C>), MatrixError> {
+    // cyclic Jacobi for Hermitian A, A <- D
+    // returns the real eigenvalues in ascending order and the unitary V with matching columns, A = V D V^H
+    // each rotation first removes the phase of a_pq with diag(1, e^-i phi) and then applies a real rotation
+    // fails with NoConvergence if row index is still rotated in sweep MAX_SWEEPS, e.g. for NaN elements
+    let n = a.num_cols;
+    check_square(a.num_rows, n)?;
+    let mut v = Matrix::complex_idty(n);
+    let tiny = f64::EPSILON * f64::EPSILON * a.iter().map(|z| z.norm_sqr()).sum::<f64>().sqrt();
+
+    let mut sweeps = 0;
+    loop {
+        let mut rotated: Option<usize> = None;
+        for p in 0..n {
+            for q in p+1..n {
+                let (apq, app, aqq) = (a[q][p], a[p][p].re, a[q][q].re);
+                if apq.norm() <= f64::EPSILON * (app.abs() + aqq.abs()) + tiny {continue}
+                let phase = Complex::from_polar(1.0, -apq.arg());
+                let theta = 0.5 * f64::atan2(2.0 * apq.norm(), aqq - app);
+                let (c, s) = (theta.cos(), theta.sin());
+                for i in 0..n {  // A <- A U
+                    let (aip, aiq) = (a[p][i], a[q][i] * phase);
+                    a[p][i] = aip * c - aiq * s;
+                    a[q][i] = aip * s + aiq * c;
+                }
+                for j in 0..n {  // A <- U^H A
+                    let (apj, aqj) = (a[j][p], a[j][q] * phase.conj());
+                    a[j][p] = apj * c - aqj * s;
+                    a[j][q] = apj * s + aqj * c;
+                }
+                a[q][p] = ZERO;
+                a[p][q] = ZERO;
+                for i in 0..n {  // V <- V U
+                    let (vip, viq) = (v[p][i], v[q][i] * phase);
+                    v[p][i] = vip * c - viq * s;
+                    v[q][i] = vip * s + viq * c;
+                }
+                rotated.get_or_insert(p);
+            }
+        }
+        sweeps += 1;
+        match rotated {
+            None => break,
+            Some(p) if sweeps == MAX_SWEEPS => return Err(MatrixError::NoConvergence {index: p}),
+            Some(_) => {},
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[i][i].re.total_cmp(&a[j][j].re));
+    let mut v_sorted = Matrix::complex_zeros(n, n);
+    for (j, &k) in order.iter().enumerate() {
+        v_sorted[j].clone_from_slice(&v[k]);
+    }
+    return Ok((order.iter().map(|&k| a[k][k].re).collect(), v_sorted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_close(a: &Matrix<C>, b: &Matrix<C>, acc: f64) -> bool {
+        a.iter().zip(b.iter()).fold(true, |acc_, (x, y)| acc_ && (x - y).norm() < acc)
+    }
+
+    fn test_matrix() -> Matrix<C> {
+        let re = Matrix::from_data(vec![2.0, 1.0, 0.0, -1.0, 3.0, 1.0, 4.0, 0.0, 1.0], 3, 3);
+        let im = Matrix::from_data(vec![1.0, 0.0, -2.0, 0.0, 1.0, 0.5, 1.0, -1.0, 0.0], 3, 3);
+        Matrix::from_parts(&re, &im)
+    }
+
+    #[test]
+    fn test_qr() {
+        let a = test_matrix();
+        let mut q = a.clone();
+        let mut r = Matrix::complex_zeros(3, 3);
+        qr_decomp(&mut q, &mut r);
+        assert!(is_close(&(q.conj_transpose() * &q), &Matrix::complex_idty(3), 1e-14));
+        assert!(is_close(&(&q * &r), &a, 1e-14));
+
+        let x = Matrix::from_data(vec![Complex::new(1.0, 1.0), Complex::new(0.0, -2.0), Complex::new(3.0, 0.0)], 3, 1);
+        assert!(is_close(&qr_solve(&q, &r, &(&a * &x)), &x, 1e-13));
+    }
+
+    #[test]
+    fn test_jacobi_hermitian() {
+        let b = test_matrix();
+        let h = &b + b.conj_transpose();
+        let mut d = h.clone();
+        let (eigvals, v) = jacobi_hermitian(&mut d).unwrap();
+        assert!(eigvals.windows(2).all(|w| w[0] <= w[1]));
+        assert!(is_close(&(v.conj_transpose() * &v), &Matrix::complex_idty(3), 1e-14));
+        let mut lambda = Matrix::complex_zeros(3, 3);
+        for i in 0..3 {lambda[i][i] = Complex::new(eigvals[i], 0.0)}
+        assert!(is_close(&(&v * lambda * v.conj_transpose()), &h, 1e-13));
+
+        // Pauli y has eigenvalues -1 and 1
+        let mut sigma_y = Matrix::from_data(vec![ZERO, Complex::new(0.0, 1.0), Complex::new(0.0, -1.0), ZERO], 2, 2);
+        let (eigvals, _) = jacobi_hermitian(&mut sigma_y).unwrap();
+        assert!((eigvals[0] + 1.0).abs() < 1e-15 && (eigvals[1] - 1.0).abs() < 1e-15);
+
+        let mut nan = h.clone();
+        nan[1][0] = Complex::new(f64::NAN, 0.0);
+        assert!(matches!(jacobi_hermitian(&mut nan), Err(MatrixError::NoConvergence {..})));
+        assert_eq!(jacobi_hermitian(&mut Matrix::complex_zeros(2, 3)), Err(MatrixError::NotSquare {shape: (2, 3)}));
+    }
+}
@@ -1,4 +1,5 @@
-use super::Matrix;
+use super::{Matrix, AsMatrixView, AsMatrixViewMut, MatrixError, check_square};
+use super::cholesky;
 
 fn are_close(a: f64, b: f64) -> bool {
     let acc = 1e-9;
@@ -8,42 +9,50 @@ fn are_close(a: f64, b: f64) -> bool {
     return false;
 }
 
-fn times_j(a: &mut Matrix<f64>, p: usize, q: usize, theta: f64) {
+pub(super) const MAX_SWEEPS: usize = 100;
+
+pub(super) fn times_j(a: &mut impl AsMatrixViewMut<f64>, p: usize, q: usize, theta: f64) {
+    let mut a = a.as_view_mut();
     let (c, s) = (theta.cos(), theta.sin());
     for i in 0..a.num_rows {
-        let (aip, aiq) = (a[p][i], a[q][i]);
-        a[p][i] = c * aip - s * aiq;
-        a[q][i] = s * aip + c * aiq;
+        let (aip, aiq) = (a[(i, p)], a[(i, q)]);
+        a[(i, p)] = c * aip - s * aiq;
+        a[(i, q)] = s * aip + c * aiq;
     }
 }
 
-fn j_times(a: &mut Matrix<f64>, p: usize, q: usize, theta: f64) {
+pub(super) fn j_times(a: &mut impl AsMatrixViewMut<f64>, p: usize, q: usize, theta: f64) {
+    let mut a = a.as_view_mut();
     let (c, s) = (theta.cos(), theta.sin());
     for j in 0..a.num_cols {
-        let (apj, aqj) = (a[j][p], a[j][q]);
-        a[j][p] = c * apj + s * aqj;
-        a[j][q] = -s * apj + c * aqj;
+        let (apj, aqj) = (a[(p, j)], a[(q, j)]);
+        a[(p, j)] = c * apj + s * aqj;
+        a[(q, j)] = -s * apj + c * aqj;
     }
 }
 
-pub fn jacobi_cyclic(a: &mut Matrix<f64>) -> Matrix<f64> {
+pub fn jacobi_cyclic(a: &mut impl AsMatrixViewMut<f64>) -> Matrix<f64> {
     // makes A diagonal -> D
     // returns transformation matrix such that A = V D V^T
+    // gives up silently after MAX_SWEEPS sweeps, see jacobi_cyclic_with for convergence diagnostics
+    let mut a = a.as_view_mut();
     let n = a.num_cols;
     let mut v = Matrix::<f64>::idty(n);
     let mut changed = true;
-    while changed {
+    let mut sweeps = 0;
+    while changed && sweeps < MAX_SWEEPS {
         changed = false;
+        sweeps += 1;
         for p in 0..n-1 {
             for q in p+1..n {
-                let (apq, app, aqq) = (a[q][p], a[p][p], a[q][q]);
+                let (apq, app, aqq) = (a[(p, q)], a[(p, p)], a[(q, q)]);
                 let theta = 0.5 * f64::atan2(2.0 * apq, aqq - app);
                 let (c, s) = (theta.cos(), theta.sin());
                 let new_app = c*c*app-2.0*s*c*apq+s*s*aqq;
                 let new_aqq = s*s*app+2.0*s*c*apq+c*c*aqq;
                 if !are_close(new_app, app) || !are_close(new_aqq, aqq) {
-                    times_j(a, p, q, theta);
-                    j_times(a, p, q, -theta);
+                    times_j(&mut a, p, q, theta);
+                    j_times(&mut a, p, q, -theta);
                     times_j(&mut v, p, q, theta);
                     changed = true;
                 }
@@ -53,20 +62,24 @@ pub fn jacobi_cyclic(a: &mut Matrix<f64>) -> Matrix<f64> {
     return v
 }
 
-pub fn jacobi_cyclic_optimised(a: &mut Matrix<f64>) -> (Vec<f64>, Matrix<f64>) {
+pub fn jacobi_cyclic_optimised(a: &mut impl AsMatrixViewMut<f64>) -> (Vec<f64>, Matrix<f64>) {
     // preserves lower triangle and diagonal of A 
     // At the end, upper triangle is zero
+    // gives up silently after MAX_SWEEPS sweeps like jacobi_cyclic
+    let mut a = a.as_view_mut();
     let n = a.num_cols;
     let mut v = Matrix::<f64>::idty(n);
     let mut eigenvalues = Vec::with_capacity(n);
-    for i in 0..n {eigenvalues.push(a[i][i])}
+    for i in 0..n {eigenvalues.push(a[(i, i)])}
     
     let mut changed = true;
-    while changed {
+    let mut sweeps = 0;
+    while changed && sweeps < MAX_SWEEPS {
         changed = false;
+        sweeps += 1;
         for p in 0..n-1 {
             for q in p+1..n {
-                let (apq, app, aqq) = (a[q][p], eigenvalues[p], eigenvalues[q]);
+                let (apq, app, aqq) = (a[(p, q)], eigenvalues[p], eigenvalues[q]);
                 let theta = 0.5 * f64::atan2(2.0 * apq, aqq - app);
                 let (c, s) = (theta.cos(), theta.sin());
                 let new_app = c*c*app-2.0*s*c*apq+s*s*aqq;
@@ -76,21 +89,21 @@ pub fn jacobi_cyclic_optimised(a: &mut Matrix<f64>) -> (Vec<f64>, Matrix<f64>) {
                     changed = true;  // do one more cycle after this one
 
                     for i in 0..p {  // update both collumn p and q up to i=p-1
-                        let (aip, aiq) = (a[p][i], a[q][i]);
-                        a[p][i] = c * aip - s * aiq;
-                        a[q][i] = s * aip + c * aiq;
+                        let (aip, aiq) = (a[(i, p)], a[(i, q)]);
+                        a[(i, p)] = c * aip - s * aiq;
+                        a[(i, q)] = s * aip + c * aiq;
                     }
                     for j in q+1..n {  // update both row p and q from j=q+1
-                        let (apj, aqj) = (a[j][p], a[j][q]);
-                        a[j][p] = c * apj - s * aqj;
-                        a[j][q] = s * apj + c * aqj;
+                        let (apj, aqj) = (a[(p, j)], a[(q, j)]);
+                        a[(p, j)] = c * apj - s * aqj;
+                        a[(q, j)] = s * apj + c * aqj;
                     }
                     for k in p+1..q {  // update the in-betweens
-                        let (apk, akq) = (a[k][p], a[q][k]);
-                        a[k][p] = c * apk - s * akq;
-                        a[q][k] = c * akq + s * apk;
+                        let (apk, akq) = (a[(p, k)], a[(k, q)]);
+                        a[(p, k)] = c * apk - s * akq;
+                        a[(k, q)] = c * akq + s * apk;
                     }
-                    a[q][p] = 0.0;  // the zeroed out element
+                    a[(p, q)] = 0.0;  // the zeroed out element
                     eigenvalues[p] = new_app;  // the diagonal values
                     eigenvalues[q] = new_aqq;
                     
@@ -102,9 +115,253 @@ pub fn jacobi_cyclic_optimised(a: &mut Matrix<f64>) -> (Vec<f64>, Matrix<f64>) {
     return (eigenvalues, v)
 }
 
-pub fn hessenberg(a: &mut Matrix<f64>) -> Matrix<f64> {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    // rotate every non-zero off-diagonal element
+    None,
+    // rotate only if the rotation moves a diagonal element by more than the tolerance, the criterion of jacobi_cyclic,
+    // since that change is of order a_pq^2 the off-diagonal elements only fall to about the square root of the tolerance
+    ValueChange,
+    // the first three sweeps skip elements below 0.2 S / n^2, with S the sum of the off-diagonal magnitudes,
+    // later sweeps zero elements that are negligible against both diagonal elements, see Numerical Recipes
+    Classic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JacobiOptions {
+    // converged when the off-diagonal Frobenius norm is at most tolerance times that of A,
+    // or for Threshold::ValueChange when a sweep makes no rotation
+    pub tolerance: f64,
+    pub max_sweeps: usize,
+    pub threshold: Threshold,
+}
+
+impl Default for JacobiOptions {
+    fn default() -> Self {
+        Self {tolerance: 1e-14, max_sweeps: 50, threshold: Threshold::Classic}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JacobiResult {
+    // eigenvalues in ascending order, eigenvectors in the matching columns
+    pub eigenvalues: Vec<f64>,
+    pub eigenvectors: Matrix<f64>,
+    pub sweeps: usize,
+    pub converged: bool,
+}
+
+fn off_diagonal_norm(a: &Matrix<f64>) -> f64 {
+    (0..a.num_cols).map(|j| (0..j).map(|i| 2.0 * a[j][i] * a[j][i]).sum::<f64>()).sum::<f64>().sqrt()
+}
+
+fn rotation_angle(a: &Matrix<f64>, p: usize, q: usize) -> f64 {
+    // zeroes a_pq and leaves the smaller of the two eigenvalues in a_pp
+    0.5 * f64::atan2(2.0 * a[q][p], a[q][q] - a[p][p])
+}
+
+fn rotate(a: &mut Matrix<f64>, v: &mut Matrix<f64>, p: usize, q: usize, theta: f64) {
+    // A <- J^T A J and V <- V J
+    times_j(a, p, q, theta);
+    j_times(a, p, q, -theta);
+    times_j(v, p, q, theta);
+    a[q][p] = 0.0;
+    a[p][q] = 0.0;
+}
+
+fn sorted_result(d: &Matrix<f64>, v: &Matrix<f64>, columns: usize, sweeps: usize, converged: bool) -> JacobiResult {
+    // the first columns diagonal elements of D and columns of V, sorted by eigenvalue
+    let mut order: Vec<usize> = (0..columns).collect();
+    order.sort_by(|&i, &j| d[i][i].partial_cmp(&d[j][j]).unwrap_or(std::cmp::Ordering::Equal));
+    let mut eigenvectors = Matrix::zeros(v.num_rows, columns);
+    for (j, &k) in order.iter().enumerate() {
+        eigenvectors[j].copy_from_slice(&v[k]);
+    }
+    JacobiResult {eigenvalues: order.iter().map(|&k| d[k][k]).collect(), eigenvectors: eigenvectors, sweeps: sweeps, converged: converged}
+}
+
+pub fn jacobi_cyclic_with(a: &impl AsMatrixView<f64>, options: &JacobiOptions) -> JacobiResult {
+    // cyclic Jacobi on a copy of the symmetric matrix A, with the rotation strategy and limits of options
+    let mut d = a.as_view().to_matrix();
+    let n = d.num_cols;
+    assert!(d.num_rows == n, "Matrix is not square");
+    let mut v = Matrix::idty(n);
+    if n < 2 {return sorted_result(&d, &v, n, 0, true)}  // nothing to rotate, even if the norm is NaN
+    let target = options.tolerance * d.frobenius_norm();
+
+    for sweep in 0..options.max_sweeps {
+        if options.threshold != Threshold::ValueChange && off_diagonal_norm(&d) <= target {
+            return sorted_result(&d, &v, n, sweep, true)
+        }
+        let skip_below = match options.threshold {
+            Threshold::Classic if sweep < 3 => {
+                let sum: f64 = (0..n).map(|j| (0..j).map(|i| d[j][i].abs()).sum::<f64>()).sum();
+                0.2 * sum / (n * n) as f64
+            },
+            _ => 0.0,
+        };
+        let mut changed = false;
+        for p in 0..n {
+            for q in p+1..n {
+                let apq = d[q][p];
+                if apq == 0.0 {continue}
+                match options.threshold {
+                    Threshold::ValueChange => {
+                        let theta = rotation_angle(&d, p, q);
+                        let (c, s) = (theta.cos(), theta.sin());
+                        let (app, aqq) = (d[p][p], d[q][q]);
+                        let new_app = c*c*app - 2.0*s*c*apq + s*s*aqq;
+                        let new_aqq = s*s*app + 2.0*s*c*apq + c*c*aqq;
+                        let close = |x: f64, y: f64| (x - y).abs() < options.tolerance * x.abs().max(y.abs()).max(1.0);
+                        if close(new_app, app) && close(new_aqq, aqq) {continue}
+                        rotate(&mut d, &mut v, p, q, theta);
+                    },
+                    Threshold::Classic if sweep > 3 && d[p][p].abs() + 100.0 * apq.abs() == d[p][p].abs()
+                        && d[q][q].abs() + 100.0 * apq.abs() == d[q][q].abs() => {
+                        d[q][p] = 0.0;
+                        d[p][q] = 0.0;
+                    },
+                    _ if apq.abs() > skip_below => {
+                        let theta = rotation_angle(&d, p, q);
+                        rotate(&mut d, &mut v, p, q, theta);
+                    },
+                    _ => continue,
+                }
+                changed = true;
+            }
+        }
+        if options.threshold == Threshold::ValueChange && !changed {
+            return sorted_result(&d, &v, n, sweep + 1, true)
+        }
+    }
+    let converged = options.threshold != Threshold::ValueChange && off_diagonal_norm(&d) <= target;
+    return sorted_result(&d, &v, n, options.max_sweeps, converged)
+}
+
+pub fn jacobi_max_element(a: &impl AsMatrixView<f64>, options: &JacobiOptions) -> JacobiResult {
+    // classic Jacobi, every rotation annihilates the largest off-diagonal element
+    // sweeps counts rotations in units of n (n - 1) / 2, the number of rotations in a cyclic sweep, and threshold is ignored
+    let mut d = a.as_view().to_matrix();
+    let n = d.num_cols;
+    assert!(d.num_rows == n, "Matrix is not square");
+    let mut v = Matrix::idty(n);
+    if n < 2 {return sorted_result(&d, &v, n, 0, true)}
+    let target = options.tolerance * d.frobenius_norm();
+    let per_sweep = (n * n.saturating_sub(1) / 2).max(1);
+
+    let mut rotations = 0;
+    while rotations < options.max_sweeps * per_sweep {
+        if off_diagonal_norm(&d) <= target {
+            return sorted_result(&d, &v, n, (rotations + per_sweep - 1) / per_sweep, true)
+        }
+        let (mut p, mut q) = (0, 1);
+        for j in 1..n {
+            for i in 0..j {
+                if d[j][i].abs() > d[q][p].abs() {(p, q) = (i, j)}
+            }
+        }
+        let theta = rotation_angle(&d, p, q);
+        rotate(&mut d, &mut v, p, q, theta);
+        rotations += 1;
+    }
+    let converged = off_diagonal_norm(&d) <= target;
+    return sorted_result(&d, &v, n, options.max_sweeps, converged)
+}
+
+pub fn jacobi_value_by_value(a: &impl AsMatrixView<f64>, num_values: usize, options: &JacobiOptions) -> JacobiResult {
+    // eigenvalue-by-eigenvalue Jacobi for the num_values lowest eigenvalues:
+    // row p is rotated against every later row until its off-diagonal part vanishes, which leaves the p'th smallest eigenvalue in a_pp
+    // sweeps counts passes over a row summed over the rows, and threshold is ignored
+    let mut d = a.as_view().to_matrix();
+    let n = d.num_cols;
+    assert!(d.num_rows == n, "Matrix is not square");
+    assert!(num_values <= n, "Non-compatible dimensions!");
+    let mut v = Matrix::idty(n);
+    if n < 2 {return sorted_result(&d, &v, num_values, 0, true)}
+    let target = options.tolerance * d.frobenius_norm();
+
+    let mut sweeps = 0;
+    for p in 0..num_values {
+        loop {
+            let row_norm = (p+1..n).map(|q| d[q][p] * d[q][p]).sum::<f64>().sqrt();
+            if row_norm <= target {break}
+            if sweeps == options.max_sweeps {
+                return sorted_result(&d, &v, p, sweeps, false)
+            }
+            for q in p+1..n {
+                if d[q][p] == 0.0 {continue}
+                let theta = rotation_angle(&d, p, q);
+                rotate(&mut d, &mut v, p, q, theta);
+            }
+            sweeps += 1;
+        }
+    }
+    return sorted_result(&d, &v, num_values, sweeps, true)
+}
+
+fn lower_solve(l: &Matrix<f64>, b: &mut Matrix<f64>) {
+    // B <- L^-1 B for lower triangular L
+    for k in 0..b.num_cols {
+        let x = &mut b[k];
+        for i in 0..l.num_rows {
+            let mut sum = x[i];
+            for j in 0..i {
+                sum -= l[j][i] * x[j];
+            }
+            x[i] = sum / l[i][i];
+        }
+    }
+}
+
+fn lower_transpose_solve(l: &Matrix<f64>, b: &mut Matrix<f64>) {
+    // B <- L^-T B for lower triangular L, row i of L^T is column i of L
+    for k in 0..b.num_cols {
+        let x = &mut b[k];
+        for i in (0..l.num_rows).rev() {
+            let mut sum = x[i];
+            for j in i+1..l.num_rows {
+                sum -= l[i][j] * x[j];
+            }
+            x[i] = sum / l[i][i];
+        }
+    }
+}
+
+pub fn generalised_symmetric(a: &impl AsMatrixView<f64>, b: &impl AsMatrixView<f64>, options: &JacobiOptions) -> Result<JacobiResult, MatrixError> {
+    // A x = lambda B x for symmetric A and symmetric positive definite B, only the lower triangle of B is read
+    // with B = L L^T this is the standard problem C y = lambda y for C = L^-1 A L^-T and x = L^-T y,
+    // so the eigenvectors are B-orthonormal, X^T B X = I
+    // fails with NotPositiveDefinite if the Cholesky factorisation of B breaks down
+    let (a, mut l) = (a.as_view().to_matrix(), b.as_view().to_matrix());
+    let n = a.num_rows;
+    check_square(n, a.num_cols)?;
+    if l.num_rows != n || l.num_cols != n {
+        return Err(MatrixError::DimensionMismatch {operation: "generalised_symmetric", lhs: (n, n), rhs: (l.num_rows, l.num_cols)})
+    }
+    cholesky::decomp(&mut l)?;
+
+    // L^-1 A L^-T = L^-1 (L^-1 A)^T as A is symmetric
+    let mut c = a;
+    lower_solve(&l, &mut c);
+    c = c.transpose();
+    lower_solve(&l, &mut c);
+    for j in 0..n {
+        for i in j+1..n {
+            let mean = 0.5 * (c[j][i] + c[i][j]);
+            c[j][i] = mean;
+            c[i][j] = mean;
+        }
+    }
+
+    let mut result = jacobi_cyclic_with(&c, options);
+    lower_transpose_solve(&l, &mut result.eigenvectors);
+    return Ok(result)
+}
+
+pub fn hessenberg(a: &mut impl AsMatrixViewMut<f64>) -> Matrix<f64> {
     // puts A to upper-Hessenberg form A <- H
     // returns transformation matrix such that A = V H V^T
+    let mut a = a.as_view_mut();
     let n = a.num_cols;
     let mut v = Matrix::<f64>::idty(n);
     
@@ -112,8 +369,8 @@ pub fn hessenberg(a: &mut Matrix<f64>) -> Matrix<f64> {
         for q in p+1..n {
             let theta = f64::atan2(-a.get(q, p-1), a.get(p, p-1));
             
-            times_j(a, p, q, theta);
-            j_times(a, p, q, -theta);
+            times_j(&mut a, p, q, theta);
+            j_times(&mut a, p, q, -theta);
             times_j(&mut v, p, q, theta);
         }
     }
@@ -122,21 +379,52 @@ pub fn hessenberg(a: &mut Matrix<f64>) -> Matrix<f64> {
 }
 
 
-pub fn determinant_upper_hessenberg(h: &Matrix<f64>) -> f64 {
+fn hessenberg_elimination(h: &impl AsMatrixView<f64>) -> (Vec<f64>, f64) {
+    // Gaussian elimination with partial pivoting, which for upper Hessenberg form
+    // only ever swaps neighbouring rows and touches a single row per step, O(n^2)
+    // returns the pivots and the sign of the row permutation
+    let h = h.as_view();
     let n = h.num_rows;
     assert!(h.num_cols == n, "Matrix is not square");
-    let indices: Vec<usize> = (0..n).collect();
-    return rec_det_hessenberg(h, &indices, &indices)
+    let mut pivots = Vec::with_capacity(n);
+    let mut sign = 1.0;
+    let mut row: Vec<f64> = (0..n).map(|j| h.get(0, j)).collect();  // current row k, columns k..n are in use
+    for k in 0..n {
+        if k + 1 == n {
+            pivots.push(row[k]);
+            break
+        }
+        let mut next: Vec<f64> = (0..n).map(|j| if j < k {0.0} else {h.get(k+1, j)}).collect();
+        if next[k].abs() > row[k].abs() {
+            std::mem::swap(&mut row, &mut next);
+            sign = -sign;
+        }
+        pivots.push(row[k]);
+        if row[k] != 0.0 {
+            let m = next[k] / row[k];
+            for j in k+1..n {
+                next[j] -= m * row[j];
+            }
+        }
+        row = next;
+    }
+    return (pivots, sign)
+}
+
+pub fn determinant_upper_hessenberg(h: &impl AsMatrixView<f64>) -> f64 {
+    let (pivots, sign) = hessenberg_elimination(h);
+    return sign * pivots.iter().product::<f64>()
 }
 
-fn rec_det_hessenberg(h: &Matrix<f64>, rows: &[usize], cols: &[usize]) -> f64 {
-    if rows.len() == 1 {
-        return h.get(rows[0], cols[0])
+pub fn log_determinant_upper_hessenberg(h: &impl AsMatrixView<f64>) -> (f64, f64) {
+    // returns (log|det H|, sign of det H), safe from overflow for large matrices
+    let (pivots, mut sign) = hessenberg_elimination(h);
+    let mut log_det = 0.0;
+    for p in pivots {
+        log_det += p.abs().ln();
+        sign *= if p == 0.0 {0.0} else {p.signum()};
     }
-    let det1 = rec_det_hessenberg(h, &rows[1..], &cols[1..]);
-    let det2 = rec_det_hessenberg(h, &[&rows[0..1], &rows[2..]].concat(), &cols[1..]);
-    let det = h.get(rows[0], cols[0]) * det1 - h.get(rows[1], cols[0]) * det2;
-    return det
+    return (log_det, sign)
 }
 
 
@@ -203,4 +491,122 @@ mod tests {
         let mat = Matrix::from_data(vec![1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0], 3, 3);
         assert_eq!(determinant_upper_hessenberg(&mat), 2.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_hessenberg_log_determinant() {
+        let mut mat = Matrix::<f64>::idty(400) * 10.0;
+        mat.set(-10.0, 0, 0);
+        for i in 1..400 {mat.set(1.0, i, i-1)}
+        let (log_det, sign) = log_determinant_upper_hessenberg(&mat);
+        assert!((log_det - 400.0 * 10.0_f64.ln()).abs() < 1e-10);
+        assert_eq!(sign, -1.0);
+        assert_eq!(determinant_upper_hessenberg(&mat), f64::NEG_INFINITY);
+
+        let mat = Matrix::from_data(vec![1.0, 2.0, 0.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], 3, 3);
+        let (log_det, sign) = log_determinant_upper_hessenberg(&mat);
+        assert!((sign * log_det.exp() - 9.0).abs() < 1e-13);
+        assert!((determinant_upper_hessenberg(&mat) - 9.0).abs() < 1e-13);
+    }
+
+    fn check_decomposition(a: &Matrix<f64>, result: &JacobiResult, tol: f64) {
+        // A v_k = lambda_k v_k with orthonormal v_k and ascending lambda_k
+        let v = &result.eigenvectors;
+        assert!((v.transpose() * v - Matrix::idty(v.num_cols)).max_norm() < tol);
+        for (k, &lambda) in result.eigenvalues.iter().enumerate() {
+            assert!((a * v.col(k) - v.col(k) * lambda).max_norm() < tol * a.max_norm());
+        }
+        assert!(result.eigenvalues.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_jacobi_options() {
+        let a = Matrix::<f64>::hilbert(6) + {let band = [1.0, 0.5, 0.0, 0.0, 0.0, 0.0]; Matrix::toeplitz(&band, &band)};
+        for &threshold in &[Threshold::None, Threshold::Classic, Threshold::ValueChange] {
+            let result = jacobi_cyclic_with(&a, &JacobiOptions {threshold: threshold, ..Default::default()});
+            assert!(result.converged && result.sweeps > 1 && result.sweeps < 20);
+            // the diagonal moves by about a_pq^2, so value changes below 1e-14 leave a_pq around 1e-7
+            check_decomposition(&a, &result, if threshold == Threshold::ValueChange {1e-6} else {1e-12});
+        }
+
+        let limited = jacobi_cyclic_with(&a, &JacobiOptions {max_sweeps: 1, ..Default::default()});
+        assert!(!limited.converged && limited.sweeps == 1);
+
+        let diagonal = jacobi_cyclic_with(&Matrix::from_data(vec![3.0, 0.0, 0.0, -1.0], 2, 2), &JacobiOptions::default());
+        assert_eq!((diagonal.eigenvalues, diagonal.sweeps, diagonal.converged), (vec![-1.0, 3.0], 0, true));
+
+        let mut nan = a.clone();
+        nan[2][1] = f64::NAN;
+        let result = jacobi_cyclic_with(&nan, &JacobiOptions::default());
+        assert!(!result.converged && result.sweeps == 50);
+    }
+
+    #[test]
+    fn test_jacobi_variants() {
+        let a = Matrix::new(vec![vec![4.0, 1.0, -2.0, 2.0], vec![1.0, 2.0, 0.0, 1.0], vec![-2.0, 0.0, 3.0, -2.0], vec![2.0, 1.0, -2.0, -1.0]]);
+        let cyclic = jacobi_cyclic_with(&a, &JacobiOptions::default());
+
+        let max_element = jacobi_max_element(&a, &JacobiOptions::default());
+        assert!(max_element.converged);
+        check_decomposition(&a, &max_element, 1e-12);
+        for (x, y) in max_element.eigenvalues.iter().zip(cyclic.eigenvalues.iter()) {assert!((x - y).abs() < 1e-12)}
+
+        let lowest = jacobi_value_by_value(&a, 2, &JacobiOptions::default());
+        assert!(lowest.converged && lowest.eigenvectors.num_cols == 2);
+        check_decomposition(&a, &lowest, 1e-12);
+        for (x, y) in lowest.eigenvalues.iter().zip(cyclic.eigenvalues.iter()) {assert!((x - y).abs() < 1e-12)}
+
+        let limited = jacobi_value_by_value(&a, 4, &JacobiOptions {max_sweeps: 2, ..Default::default()});
+        assert!(!limited.converged && limited.sweeps == 2 && limited.eigenvalues.len() < 4);
+
+        for n in 0..2 {
+            let a = Matrix::from_data(vec![f64::NAN; n], n, n);
+            for result in [
+                jacobi_cyclic_with(&a, &JacobiOptions::default()),
+                jacobi_max_element(&a, &JacobiOptions::default()),
+                jacobi_value_by_value(&a, n, &JacobiOptions::default()),
+            ] {
+                assert!(result.converged && result.sweeps == 0 && result.eigenvalues.len() == n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generalised_symmetric() {
+        // two coupled oscillators with masses 1 and 2 and unit springs, roots of 2 lambda^2 - 5 lambda + 1
+        let a = Matrix::new(vec![vec![2.0, -1.0], vec![-1.0, 1.0]]);
+        let b = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 2.0]]);
+        let result = generalised_symmetric(&a, &b, &JacobiOptions::default()).unwrap();
+        assert!(result.converged);
+        let expected = [(5.0 - 17f64.sqrt()) / 4.0, (5.0 + 17f64.sqrt()) / 4.0];
+        for (x, y) in result.eigenvalues.iter().zip(expected.iter()) {assert!((x - y).abs() < 1e-14)}
+
+        let x = &result.eigenvectors;
+        assert!((x.transpose() * &b * x - Matrix::idty(2)).max_norm() < 1e-14);
+        assert!((&a * x - &b * x * Matrix::diag(&result.eigenvalues)).max_norm() < 1e-14);
+
+        // a non-orthogonal basis, B = I reproduces the standard problem
+        let a = Matrix::<f64>::hilbert(5);
+        let b = Matrix::from_fn(5, 5, |i, j| 0.5f64.powi((i as i32 - j as i32).abs()));
+        let result = generalised_symmetric(&a, &b, &JacobiOptions::default()).unwrap();
+        let x = &result.eigenvectors;
+        assert!((x.transpose() * &b * x - Matrix::idty(5)).max_norm() < 1e-12);
+        assert!((&a * x - &b * x * Matrix::diag(&result.eigenvalues)).max_norm() < 1e-12);
+        let standard = generalised_symmetric(&a, &Matrix::idty(5), &JacobiOptions::default()).unwrap();
+        assert_eq!(standard, jacobi_cyclic_with(&a, &JacobiOptions::default()));
+    }
+
+    #[test]
+    fn test_generalised_symmetric_errors() {
+        let a = Matrix::idty(2);
+        let indefinite = Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 1.0]]);
+        assert_eq!(generalised_symmetric(&a, &indefinite, &JacobiOptions::default()), Err(MatrixError::NotPositiveDefinite {pivot: 1}));
+        assert_eq!(
+            generalised_symmetric(&a, &Matrix::idty(3), &JacobiOptions::default()),
+            Err(MatrixError::DimensionMismatch {operation: "generalised_symmetric", lhs: (2, 2), rhs: (3, 3)})
+        );
+        assert_eq!(
+            generalised_symmetric(&Matrix::zeros(2, 3), &a, &JacobiOptions::default()),
+            Err(MatrixError::NotSquare {shape: (2, 3)})
+        );
+    }
+}
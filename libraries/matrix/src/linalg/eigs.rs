@@ -0,0 +1,382 @@
+use super::Matrix;
+use super::iterative::{gmres, norm, dot, axpy};
+use super::eig::{jacobi_cyclic_with, JacobiOptions};
+use super::tridiagonal;
+use std::iter::zip;
+
+// Iterative eigensolvers for a few eigenpairs of a symmetric operator A on vectors of length n,
+// given as a matrix-vector closure, see iterative::operator for dense matrices. All solvers return
+// Ok(EigenPairs) when every residual norm |A x - lambda x| is below acc times an estimate of |A|,
+// and Err with the same content otherwise, with every entry NaN if A produced a vector that is not finite.
+
+type Operator<'a> = &'a dyn Fn(&Vec<f64>) -> Vec<f64>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Which {
+    Lowest,
+    Highest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reorthogonalisation {
+    // against every previous Lanczos vector, O(n m) work in step m
+    Full,
+    // only against the Ritz vectors at the wanted end that have converged to sqrt(eps),
+    // the directions in which orthogonality is lost, see Parlett and Scott (1979)
+    Selective,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EigenPairs {
+    // eigenvalues from the wanted end inwards, eigenvectors in the matching columns,
+    // and residual norms |A x - lambda x| computed with A
+    pub eigenvalues: Vec<f64>,
+    pub eigenvectors: Matrix<f64>,
+    pub residuals: Vec<f64>,
+    pub iterations: u32,
+}
+
+fn start_vector(n: usize, seed: usize) -> Vec<f64> {
+    // deterministic but without structure, so it is not orthogonal to an eigenvector by accident
+    (0..n).map(|i| {
+        let h = ((i as u64 + 1) << 32 | seed as u64).wrapping_mul(0x9E3779B97F4A7C15);
+        (h >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }).collect()
+}
+
+fn orthogonalise(w: &mut Vec<f64>, basis: &[Vec<f64>]) {
+    // classical Gram-Schmidt twice, which is enough to keep w orthogonal to the basis to working precision
+    for _ in 0..2 {
+        let coefficients: Vec<f64> = basis.iter().map(|q| dot(q, w)).collect();
+        for (c, q) in zip(coefficients, basis) {axpy(-c, q, w)}
+    }
+}
+
+fn normalise(w: &mut Vec<f64>) -> f64 {
+    let length = norm(w);
+    for wi in w.iter_mut() {*wi /= length}
+    return length
+}
+
+fn push_orthonormal(basis: &mut Vec<Vec<f64>>, mut w: Vec<f64>, n: usize) -> bool {
+    // appends w orthonormalised against the basis, or a fresh start vector if w lies in its span
+    // false, leaving the basis as it is, if w is not finite
+    let mut seed = n + basis.len();
+    loop {
+        let length = norm(&w);
+        if !length.is_finite() {return false}
+        orthogonalise(&mut w, basis);
+        if norm(&w) > 1e-8 * length {break}
+        w = start_vector(n, seed);
+        seed += 1;
+    }
+    normalise(&mut w);
+    basis.push(w);
+    return true
+}
+
+fn combine(basis: &[Vec<f64>], coefficients: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; basis[0].len()];
+    for (q, &c) in zip(basis, coefficients) {axpy(c, q, &mut result)}
+    return result
+}
+
+fn residual_norm(a: Operator, x: &Vec<f64>, lambda: f64) -> f64 {
+    let mut r = a(x);
+    axpy(-lambda, x, &mut r);
+    return norm(&r)
+}
+
+fn spectral_radius(a: Operator, n: usize) -> f64 {
+    // estimate of max |lambda| from below by |A x| after a few power steps
+    let mut x = start_vector(n, 0);
+    normalise(&mut x);
+    let mut rho = 0.0;
+    for _ in 0..20 {
+        x = a(&x);
+        rho = normalise(&mut x);
+        if !(rho > 0.0) {return 0.0}
+    }
+    return rho
+}
+
+fn finish(mut pairs: Vec<(f64, Vec<f64>, f64)>, iterations: u32, tol: f64, key: impl Fn(f64) -> f64) -> Result<EigenPairs, EigenPairs> {
+    // pairs of (eigenvalue, eigenvector, residual norm) sorted by key of the eigenvalue
+    pairs.sort_by(|x, y| key(x.0).partial_cmp(&key(y.0)).unwrap_or(std::cmp::Ordering::Equal));
+    let converged = pairs.iter().all(|pair| pair.2 <= tol);
+    let result = EigenPairs {
+        eigenvalues: pairs.iter().map(|pair| pair.0).collect(),
+        residuals: pairs.iter().map(|pair| pair.2).collect(),
+        eigenvectors: Matrix::new(pairs.into_iter().map(|pair| pair.1).collect()),
+        iterations: iterations,
+    };
+    if converged {Ok(result)} else {Err(result)}
+}
+
+fn diverged(n: usize, k: usize, iterations: u32) -> Result<EigenPairs, EigenPairs> {
+    Err(EigenPairs {
+        eigenvalues: vec![f64::NAN; k],
+        eigenvectors: Matrix::from_fn(n, k, |_, _| f64::NAN),
+        residuals: vec![f64::NAN; k],
+        iterations: iterations,
+    })
+}
+
+fn wanted_first(which: Which) -> impl Fn(f64) -> f64 {
+    move |lambda| match which {Which::Lowest => lambda, Which::Highest => -lambda}
+}
+
+pub fn power(a: &impl Fn(&Vec<f64>) -> Vec<f64>, n: usize, k: usize, which: Which, options: Option<(u32, f64)>) -> Result<EigenPairs, EigenPairs> {
+    // subspace iteration on A + rho I for the highest or rho I - A for the lowest eigenvalues, with rho an estimate of |A|,
+    // and Rayleigh-Ritz on the subspace after every step
+    // two guard vectors beyond k make the error shrink like the ratio of the shifted eigenvalues k+3 and k per iteration
+    let (max_iter, acc) = options.unwrap_or((1000, 1e-10));
+    assert!(0 < k && k <= n, "Non-compatible dimensions!");
+    let p = n.min(k + 2);
+    let rho = spectral_radius(a, n);
+    let sign = match which {Which::Lowest => -1.0, Which::Highest => 1.0};
+
+    let mut v: Vec<Vec<f64>> = Vec::with_capacity(p);
+    for j in 0..p {push_orthonormal(&mut v, start_vector(n, j), n);}
+    let mut iter = 0;
+    loop {
+        iter += 1;
+        let w: Vec<Vec<f64>> = v.iter().map(|x| a(x)).collect();
+        let h = Matrix::from_fn(p, p, |i, j| 0.5 * (dot(&v[i], &w[j]) + dot(&v[j], &w[i])));
+        let ritz = jacobi_cyclic_with(&h, &JacobiOptions::default());
+        // Ritz vectors x = V y with A x = W y, from the wanted end
+        let order: Vec<usize> = match which {Which::Lowest => (0..p).collect(), Which::Highest => (0..p).rev().collect()};
+        let theta: Vec<f64> = order.iter().map(|&c| ritz.eigenvalues[c]).collect();
+        let x: Vec<Vec<f64>> = order.iter().map(|&c| combine(&v, &ritz.eigenvectors[c])).collect();
+        let ax: Vec<Vec<f64>> = order.iter().map(|&c| combine(&w, &ritz.eigenvectors[c])).collect();
+        let residuals: Vec<f64> = (0..k).map(|i| {
+            let mut r = ax[i].clone();
+            axpy(-theta[i], &x[i], &mut r);
+            norm(&r)
+        }).collect();
+        if residuals.iter().any(|r| !r.is_finite()) {return diverged(n, k, iter)}
+        let tol = acc * theta.iter().fold(rho, |s, t| s.max(t.abs()));
+
+        if iter >= max_iter || residuals.iter().all(|&r| r <= tol) {
+            let pairs = (0..k).map(|i| (theta[i], x[i].clone(), residuals[i])).collect();
+            return finish(pairs, iter, tol, wanted_first(which))
+        }
+        v.clear();
+        for i in 0..p {  // V <- orthonormalised (rho I +- A) X
+            let mut y: Vec<f64> = x[i].iter().map(|xi| rho * xi).collect();
+            axpy(sign, &ax[i], &mut y);
+            if !push_orthonormal(&mut v, y, n) {return diverged(n, k, iter)}
+        }
+    }
+}
+
+pub fn inverse_iteration(a: &impl Fn(&Vec<f64>) -> Vec<f64>, n: usize, k: usize, shift: f64, options: Option<(u32, f64)>) -> Result<EigenPairs, EigenPairs> {
+    // the k eigenpairs nearest to shift, found one at a time in the orthogonal complement of those already found,
+    // so a shift below or above the spectrum gives the k lowest or highest
+    // every pair starts with inverse iteration with the fixed shift until its residual is below sqrt(acc) |A|,
+    // and is then refined by Rayleigh quotient iteration, which converges cubically
+    // the shifted systems are solved by GMRES, iterations counts the solves
+    let (max_iter, acc) = options.unwrap_or((1000, 1e-10));
+    assert!(0 < k && k <= n, "Non-compatible dimensions!");
+    let scale = spectral_radius(a, n).max(shift.abs());
+    let tol = acc * scale;
+
+    let mut found: Vec<Vec<f64>> = Vec::with_capacity(k);
+    let mut pairs = Vec::with_capacity(k);
+    let mut iter = 0;
+    for j in 0..k {
+        push_orthonormal(&mut found, start_vector(n, j), n);
+        let mut x = found.pop().unwrap();
+        let (mut theta, mut r);
+        loop {
+            let ax = a(&x);
+            theta = dot(&x, &ax);
+            r = residual_norm(a, &x, theta);
+            if !r.is_finite() {return diverged(n, k, iter)}
+            if r <= tol || iter >= max_iter {break}
+            iter += 1;
+
+            let sigma = if r <= acc.sqrt() * scale {theta} else {shift};
+            let shifted = |y: &Vec<f64>| {  // P (A - sigma I) P with P the projector onto the complement of the found vectors
+                let mut z = y.clone();
+                orthogonalise(&mut z, &found);
+                let mut result = a(&z);
+                axpy(-sigma, &z, &mut result);
+                orthogonalise(&mut result, &found);
+                result
+            };
+            let (mut y, _, _) = gmres(&shifted, &x, vec![0.0; n], None, n.min(50), None).unwrap_or_else(|err| err);
+            orthogonalise(&mut y, &found);
+            // a singular shifted system leaves x as it is
+            if !(normalise(&mut y) > 0.0) || y.iter().any(|yi| !yi.is_finite()) {break}
+            x = y;
+        }
+        found.push(x.clone());
+        pairs.push((theta, x, r));
+    }
+    return finish(pairs, iter, tol, |lambda| (lambda - shift).abs())
+}
+
+pub fn lanczos(a: &impl Fn(&Vec<f64>) -> Vec<f64>, n: usize, k: usize, which: Which, reorthogonalisation: Reorthogonalisation, options: Option<(u32, f64)>) -> Result<EigenPairs, EigenPairs> {
+    // Lanczos tridiagonalisation T = Q^T A Q, the Ritz pairs at the wanted end come from tridiagonal::eig after every step
+    // the Ritz pair (theta, Q s) of step m has residual norm beta_m |s_m|, which decides convergence against the Gershgorin bound of T
+    // on breakdown the recurrence continues with a fresh vector, iterations counts the steps, at least k and at most n
+    // a single start vector only finds one eigenvector of a degenerate eigenvalue, unless rounding errors bring in the others
+    let (max_iter, acc) = options.unwrap_or((1000, 1e-10));
+    assert!(0 < k && k <= n, "Non-compatible dimensions!");
+    // for the highest eigenvalues the lowest of -T
+    let sign = match which {Which::Lowest => 1.0, Which::Highest => -1.0};
+
+    let mut q: Vec<Vec<f64>> = Vec::new();
+    push_orthonormal(&mut q, start_vector(n, 0), n);
+    let (mut alpha, mut beta): (Vec<f64>, Vec<f64>) = (Vec::new(), Vec::new());
+    let mut locked: Vec<Vec<f64>> = Vec::new();
+    let mut scale: f64 = 0.0;
+    loop {
+        let m = q.len();
+        let mut w = a(&q[m-1]);
+        alpha.push(dot(&q[m-1], &w));
+        axpy(-alpha[m-1], &q[m-1], &mut w);
+        if m > 1 {axpy(-beta[m-2], &q[m-2], &mut w)}
+        match reorthogonalisation {
+            Reorthogonalisation::Full => orthogonalise(&mut w, &q),
+            Reorthogonalisation::Selective => orthogonalise(&mut w, &locked),
+        }
+        let b = norm(&w);
+        if !(alpha[m-1].is_finite() && b.is_finite()) {return diverged(n, k, m as u32)}
+
+        let wanted = k.min(m);
+        let d: Vec<f64> = alpha.iter().map(|x| sign * x).collect();
+        let e: Vec<f64> = beta.iter().map(|x| sign * x).collect();
+        let (theta, s) = tridiagonal::eig(&d, &e, Some(wanted)).unwrap_or_else(|err| panic!("{}", err));  // bisection does not fail
+        scale = scale.max(alpha[m-1].abs() + b + if m > 1 {beta[m-2].abs()} else {0.0});
+        let bounds: Vec<f64> = (0..wanted).map(|i| b * s[i][m-1].abs()).collect();
+
+        if m == n || m as u32 >= max_iter.max(k as u32) || (wanted == k && bounds.iter().all(|&bound| bound <= acc * scale)) {
+            let pairs = (0..k).map(|i| {
+                let x = combine(&q, &s[i]);
+                let r = residual_norm(a, &x, sign * theta[i]);
+                (sign * theta[i], x, r)
+            }).collect();
+            return finish(pairs, m as u32, acc * scale, wanted_first(which))
+        }
+        if reorthogonalisation == Reorthogonalisation::Selective {
+            locked = (0..wanted).filter(|&i| bounds[i] <= f64::EPSILON.sqrt() * scale).map(|i| combine(&q, &s[i])).collect();
+        }
+        if b > f64::EPSILON * scale {
+            for wi in w.iter_mut() {*wi /= b}
+            beta.push(b);
+            q.push(w);
+        } else {  // invariant subspace, T continues block diagonal
+            beta.push(0.0);
+            if !push_orthonormal(&mut q, start_vector(n, m), n) {return diverged(n, k, m as u32)}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::iterative::operator;
+    use super::super::super::CooMatrix;
+
+    fn test_matrix() -> Matrix<f64> {
+        // diagonal 1..20 with a weak coupling, well separated eigenvalues
+        Matrix::from_fn(20, 20, |i, j| if i == j {(i + 1) as f64} else {0.1 / (1.0 + (i as f64 - j as f64).abs())})
+    }
+
+    fn check(a: &Matrix<f64>, result: &EigenPairs, expected: &[f64]) {
+        assert_eq!(result.eigenvalues.len(), expected.len());
+        for (x, y) in zip(&result.eigenvalues, expected) {assert!((x - y).abs() < 1e-9)}
+        let v = &result.eigenvectors;
+        assert!((v.transpose() * v - Matrix::idty(v.num_cols)).max_norm() < 1e-8);
+        for (i, &lambda) in result.eigenvalues.iter().enumerate() {
+            let r = (a * v.col(i) - v.col(i) * lambda).frobenius_norm();
+            assert!((r - result.residuals[i]).abs() < 1e-12 * a.norm_inf() && r < 1e-9 * a.norm_inf());
+        }
+    }
+
+    fn oscillator(n: usize) -> (Vec<f64>, Vec<f64>, CooMatrix<f64>) {
+        // -1/2 d^2/dx^2 + x^2/2 by central differences on [-8, 10], the low eigenvalues are close to 1/2, 3/2, 5/2, ...
+        // the interval is not symmetric, which would make the high eigenvalues come in nearly degenerate pairs
+        let h = 18.0 / (n + 1) as f64;
+        let d: Vec<f64> = (0..n).map(|i| 1.0 / (h * h) + 0.5 * (-8.0 + (i + 1) as f64 * h).powi(2)).collect();
+        let e = vec![-0.5 / (h * h); n - 1];
+        let mut a = CooMatrix::new(n, n);
+        for i in 0..n {
+            a.push(d[i], i, i);
+            if i > 0 {a.push(e[i-1], i, i-1)}
+            if i + 1 < n {a.push(e[i], i, i+1)}
+        }
+        return (d, e, a)
+    }
+
+    #[test]
+    fn test_power() {
+        let a = test_matrix();
+        let all = jacobi_cyclic_with(&a, &JacobiOptions::default()).eigenvalues;
+        let lowest = power(&operator(&a), 20, 3, Which::Lowest, None).unwrap();
+        check(&a, &lowest, &all[..3]);
+        let highest = power(&operator(&a), 20, 2, Which::Highest, None).unwrap();
+        check(&a, &highest, &[all[19], all[18]]);
+
+        let limited = power(&operator(&a), 20, 3, Which::Lowest, Some((5, 1e-10))).unwrap_err();
+        assert_eq!(limited.iterations, 5);
+    }
+
+    #[test]
+    fn test_inverse_iteration() {
+        let a = test_matrix();
+        let all = jacobi_cyclic_with(&a, &JacobiOptions::default()).eigenvalues;
+        let lowest = inverse_iteration(&operator(&a), 20, 3, 0.0, None).unwrap();
+        check(&a, &lowest, &all[..3]);
+        assert!(lowest.iterations < 100);
+
+        // nearest to 10.4 are the eigenvalues near 10 and 11
+        let interior = inverse_iteration(&operator(&a), 20, 2, 10.4, None).unwrap();
+        check(&a, &interior, &[all[9], all[10]]);
+    }
+
+    #[test]
+    fn test_lanczos() {
+        let n = 400;
+        let (d, e, a) = oscillator(n);
+        let a = a.to_csr();
+        let dense = a.to_dense();
+        let exact = tridiagonal::eigenvalues(&d, &e).unwrap();
+        let matvec = |x: &Vec<f64>| a.mul_vec(x);
+
+        for &reorthogonalisation in &[Reorthogonalisation::Full, Reorthogonalisation::Selective] {
+            let lowest = lanczos(&matvec, n, 4, Which::Lowest, reorthogonalisation, None).unwrap();
+            check(&dense, &lowest, &exact[..4]);
+            assert!((lowest.eigenvalues[0] - 0.5).abs() < 1e-3 && lowest.iterations < n as u32);
+
+            let highest = lanczos(&matvec, n, 2, Which::Highest, reorthogonalisation, None).unwrap();
+            check(&dense, &highest, &[exact[n-1], exact[n-2]]);
+        }
+
+        // a complete Krylov space of a small matrix gives every eigenvalue
+        let a = test_matrix();
+        let all = jacobi_cyclic_with(&a, &JacobiOptions::default()).eigenvalues;
+        let result = lanczos(&operator(&a), 20, 20, Which::Lowest, Reorthogonalisation::Full, None).unwrap();
+        check(&a, &result, &all);
+    }
+
+    #[test]
+    fn test_not_finite() {
+        // an operator that overflows must end the iteration instead of feeding NaN into the basis
+        let nan = |x: &Vec<f64>| x.iter().map(|_| f64::NAN).collect::<Vec<f64>>();
+        let overflow = |x: &Vec<f64>| x.iter().map(|xi| xi * f64::MAX * 10.0).collect::<Vec<f64>>();
+        for result in [
+            power(&nan, 20, 2, Which::Lowest, None),
+            power(&overflow, 20, 2, Which::Highest, None),
+            inverse_iteration(&nan, 20, 2, 0.0, None),
+            inverse_iteration(&overflow, 20, 2, 1.0, None),
+            lanczos(&nan, 20, 2, Which::Lowest, Reorthogonalisation::Full, None),
+            lanczos(&overflow, 20, 2, Which::Highest, Reorthogonalisation::Selective, None),
+        ] {
+            let result = result.unwrap_err();
+            assert!(result.eigenvalues.len() == 2 && result.eigenvalues.iter().all(|x| x.is_nan()));
+        }
+    }
+}
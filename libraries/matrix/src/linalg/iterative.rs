@@ -0,0 +1,314 @@
+use super::AsMatrixView;
+use std::iter::zip;
+
+// Krylov subspace solvers for A x = b, where the operator A and the preconditioner M^-1
+// are given as matrix-vector closures. All solvers return Ok((x, iterations, residual norms))
+// when the residual norm drops below acc * |b|, and Err with the same content otherwise.
+
+type Operator<'a> = &'a dyn Fn(&Vec<f64>) -> Vec<f64>;
+
+#[inline]
+pub(super) fn norm(v: &Vec<f64>) -> f64 {
+    v.iter().fold(0.0, |sum, vi| sum+vi*vi).sqrt()
+}
+#[inline]
+pub(super) fn dot(u: &Vec<f64>, v: &Vec<f64>) -> f64 {
+    zip(u, v).fold(0.0, |sum, (a, b)| sum + a*b)
+}
+#[inline]
+pub(super) fn axpy(alpha: f64, x: &Vec<f64>, y: &mut Vec<f64>) {
+    // y <- y + alpha x
+    for (yi, xi) in zip(y.iter_mut(), x) {*yi += alpha * xi}
+}
+
+fn apply(m: Option<Operator>, v: &Vec<f64>) -> Vec<f64> {
+    match m {
+        Some(m) => m(v),
+        None => v.clone(),
+    }
+}
+
+fn residual(a: &impl Fn(&Vec<f64>) -> Vec<f64>, b: &Vec<f64>, x: &Vec<f64>) -> Vec<f64> {
+    zip(b, a(x)).map(|(bi, axi)| bi - axi).collect()
+}
+
+pub fn operator(a: &impl AsMatrixView<f64>) -> impl Fn(&Vec<f64>) -> Vec<f64> + '_ {
+    // wraps a dense matrix or view as a matrix-vector closure
+    let a = a.as_view();
+    move |x: &Vec<f64>| {
+        let mut y = vec![0.0; a.num_rows];
+        for j in 0..a.num_cols {
+            for i in 0..a.num_rows {y[i] += x[j] * a[(i, j)]}
+        }
+        y
+    }
+}
+
+pub fn jacobi(diag: Vec<f64>) -> impl Fn(&Vec<f64>) -> Vec<f64> {
+    // M = diag(A)
+    move |r: &Vec<f64>| zip(r, &diag).map(|(ri, di)| ri / di).collect()
+}
+
+pub fn ssor(a: &impl AsMatrixView<f64>, omega: f64) -> impl Fn(&Vec<f64>) -> Vec<f64> + '_ {
+    // M = omega/(2-omega) (D/omega + L) (D/omega)^-1 (D/omega + U) with 0 < omega < 2
+    let a = a.as_view();
+    let n = a.num_rows;
+    move |r: &Vec<f64>| {
+        let mut z = r.clone();
+        for i in 0..n {  // (D/omega + L) y = r
+            for j in 0..i {
+                z[i] -= a[(i, j)] * z[j];
+            }
+            z[i] *= omega / a[(i, i)];
+        }
+        for i in 0..n {  // multiply by D/omega
+            z[i] *= a[(i, i)] / omega;
+        }
+        for i in (0..n).rev() {  // (D/omega + U) z = D/omega y
+            for j in i+1..n {
+                z[i] -= a[(i, j)] * z[j];
+            }
+            z[i] *= omega / a[(i, i)];
+        }
+        for zi in z.iter_mut() {*zi *= (2.0 - omega) / omega}
+        z
+    }
+}
+
+pub fn cg(a: &impl Fn(&Vec<f64>) -> Vec<f64>, b: &Vec<f64>, x0: Vec<f64>, m: Option<Operator>, options: Option<(u32, f64)>) -> Result<(Vec<f64>, u32, Vec<f64>), (Vec<f64>, u32, Vec<f64>)> {
+    // preconditioned conjugate gradient, A and M must be symmetric positive definite
+    let (max_iter, acc) = options.unwrap_or((1000, 1e-10));
+    let tol = acc * norm(b);
+    let mut x = x0;
+    let mut r = residual(a, b, &x);
+    let mut history = vec![norm(&r)];
+    if history[0] <= tol {return Ok((x, 0, history))}
+
+    let mut z = apply(m, &r);
+    let mut p = z.clone();
+    let mut rz = dot(&r, &z);
+    for iter in 1..=max_iter {
+        let ap = a(&p);
+        let alpha = rz / dot(&p, &ap);
+        axpy(alpha, &p, &mut x);
+        axpy(-alpha, &ap, &mut r);
+        history.push(norm(&r));
+        if *history.last().unwrap() <= tol {
+            return Ok((x, iter, history))
+        }
+
+        z = apply(m, &r);
+        let rz_new = dot(&r, &z);
+        let beta = rz_new / rz;
+        rz = rz_new;
+        for (pi, zi) in zip(p.iter_mut(), &z) {*pi = zi + beta * *pi}
+    }
+    return Err((x, max_iter, history))
+}
+
+pub fn gmres(a: &impl Fn(&Vec<f64>) -> Vec<f64>, b: &Vec<f64>, x0: Vec<f64>, m: Option<Operator>, restart: usize, options: Option<(u32, f64)>) -> Result<(Vec<f64>, u32, Vec<f64>), (Vec<f64>, u32, Vec<f64>)> {
+    // right preconditioned GMRES restarted after every restart iterations, at least one
+    let restart = restart.max(1);
+    let (max_iter, acc) = options.unwrap_or((1000, 1e-10));
+    let tol = acc * norm(b);
+    let mut x = x0;
+    let mut r = residual(a, b, &x);
+    let mut history = vec![norm(&r)];
+    let mut iter = 0;
+
+    while history.last().unwrap() > &tol && iter < max_iter {
+        let beta = norm(&r);
+        let mut v: Vec<Vec<f64>> = vec![r.iter().map(|ri| ri / beta).collect()];
+        let mut h: Vec<Vec<f64>> = Vec::with_capacity(restart);  // columns of the Hessenberg matrix
+        let (mut cs, mut sn): (Vec<f64>, Vec<f64>) = (Vec::with_capacity(restart), Vec::with_capacity(restart));
+        let mut g = vec![beta];
+
+        for j in 0..restart {
+            iter += 1;
+            let mut w = a(&apply(m, &v[j]));
+            let mut hj = Vec::with_capacity(j + 2);
+            for i in 0..=j {  // modified Gram-Schmidt
+                let hij = dot(&w, &v[i]);
+                axpy(-hij, &v[i], &mut w);
+                hj.push(hij);
+            }
+            let w_norm = norm(&w);
+            hj.push(w_norm);
+            if w_norm > 0.0 {
+                v.push(w.iter().map(|wi| wi / w_norm).collect());
+            }
+
+            for i in 0..j {  // apply the previous Givens rotations to the new column
+                let (a, b) = (hj[i], hj[i+1]);
+                hj[i] = cs[i] * a + sn[i] * b;
+                hj[i+1] = -sn[i] * a + cs[i] * b;
+            }
+            let rho = hj[j].hypot(hj[j+1]);
+            cs.push(hj[j] / rho);
+            sn.push(hj[j+1] / rho);
+            hj[j] = rho;
+            hj[j+1] = 0.0;
+            g.push(-sn[j] * g[j]);
+            g[j] *= cs[j];
+            h.push(hj);
+            history.push(g[j+1].abs());
+
+            if g[j+1].abs() <= tol || iter >= max_iter || w_norm == 0.0 {break}
+        }
+
+        let k = h.len();  // solve the k x k triangular system H y = g and update x += M^-1 V y
+        let mut y = vec![0.0; k];
+        for i in (0..k).rev() {
+            let mut sum = g[i];
+            for j in i+1..k {
+                sum -= h[j][i] * y[j];
+            }
+            y[i] = sum / h[i][i];
+        }
+        let mut update = vec![0.0; b.len()];
+        for i in 0..k {
+            axpy(y[i], &v[i], &mut update);
+        }
+        axpy(1.0, &apply(m, &update), &mut x);
+        r = residual(a, b, &x);
+        *history.last_mut().unwrap() = norm(&r);
+    }
+
+    if history.last().unwrap() <= &tol {
+        return Ok((x, iter, history))
+    }
+    return Err((x, iter, history))
+}
+
+pub fn bicgstab(a: &impl Fn(&Vec<f64>) -> Vec<f64>, b: &Vec<f64>, x0: Vec<f64>, m: Option<Operator>, options: Option<(u32, f64)>) -> Result<(Vec<f64>, u32, Vec<f64>), (Vec<f64>, u32, Vec<f64>)> {
+    // right preconditioned biconjugate gradient stabilised method
+    let (max_iter, acc) = options.unwrap_or((1000, 1e-10));
+    let tol = acc * norm(b);
+    let mut x = x0;
+    let mut r = residual(a, b, &x);
+    let mut history = vec![norm(&r)];
+    if history[0] <= tol {return Ok((x, 0, history))}
+
+    let r_hat = r.clone();
+    let (mut rho, mut alpha, mut omega) = (1.0, 1.0, 1.0);
+    let mut v = vec![0.0; b.len()];
+    let mut p = vec![0.0; b.len()];
+    for iter in 1..=max_iter {
+        let rho_new = dot(&r_hat, &r);
+        if rho_new == 0.0 {break}  // breakdown
+        let beta = (rho_new / rho) * (alpha / omega);
+        rho = rho_new;
+        for i in 0..p.len() {
+            p[i] = r[i] + beta * (p[i] - omega * v[i]);
+        }
+
+        let p_hat = apply(m, &p);
+        v = a(&p_hat);
+        alpha = rho / dot(&r_hat, &v);
+        let mut s = r.clone();
+        axpy(-alpha, &v, &mut s);
+        axpy(alpha, &p_hat, &mut x);
+        if norm(&s) <= tol {
+            history.push(norm(&s));
+            return Ok((x, iter, history))
+        }
+
+        let s_hat = apply(m, &s);
+        let t = a(&s_hat);
+        omega = dot(&t, &s) / dot(&t, &t);
+        axpy(omega, &s_hat, &mut x);
+        r = s;
+        axpy(-omega, &t, &mut r);
+        history.push(norm(&r));
+        if *history.last().unwrap() <= tol {
+            return Ok((x, iter, history))
+        }
+        if omega == 0.0 {break}  // breakdown
+    }
+    let iter = history.len() as u32 - 1;
+    return Err((x, iter, history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Matrix;
+
+    fn convection_diffusion(n: usize, v: f64) -> Matrix<f64> {
+        // -u'' + v u' with central differences, symmetric positive definite for v = 0
+        let mut a = Matrix::zeros(n, n);
+        for i in 0..n {
+            a[i][i] = 2.0 + 0.01 * i as f64;
+            if i > 0 {a[i-1][i] = -1.0 - v}
+            if i + 1 < n {a[i+1][i] = -1.0 + v}
+        }
+        return a
+    }
+
+    fn check(a: &Matrix<f64>, b: &Vec<f64>, x: &Vec<f64>) -> bool {
+        norm(&residual(&operator(a), b, x)) < 1e-8 * norm(b)
+    }
+
+    #[test]
+    fn test_cg() {
+        let a = convection_diffusion(100, 0.0);
+        let b: Vec<f64> = (0..100).map(|i| (i as f64).sin()).collect();
+        let (x, iter, history) = cg(&operator(&a), &b, vec![0.0; 100], None, None).unwrap();
+        assert!(check(&a, &b, &x));
+        assert_eq!(history.len(), iter as usize + 1);
+
+        let diag = (0..100).map(|i| a[i][i]).collect();
+        let (x, _, _) = cg(&operator(&a), &b, vec![0.0; 100], Some(&jacobi(diag)), None).unwrap();
+        assert!(check(&a, &b, &x));
+
+        let (x, ssor_iter, _) = cg(&operator(&a), &b, vec![0.0; 100], Some(&ssor(&a, 1.5)), None).unwrap();
+        assert!(check(&a, &b, &x));
+        assert!(ssor_iter < iter);
+    }
+
+    #[test]
+    fn test_matrix_free() {
+        let n = 1000;
+        let laplace = |x: &Vec<f64>| -> Vec<f64> {
+            (0..n).map(|i| {
+                let left = if i > 0 {x[i-1]} else {0.0};
+                let right = if i + 1 < n {x[i+1]} else {0.0};
+                3.0 * x[i] - left - right
+            }).collect()
+        };
+        let b = vec![1.0; n];
+        let (x, _, _) = cg(&laplace, &b, vec![0.0; n], None, None).unwrap();
+        assert!(norm(&residual(&laplace, &b, &x)) < 1e-9 * norm(&b));
+    }
+
+    #[test]
+    fn test_gmres() {
+        let a = convection_diffusion(100, 0.4);
+        let b: Vec<f64> = (0..100).map(|i| (i as f64).cos()).collect();
+        let (x, iter, history) = gmres(&operator(&a), &b, vec![0.0; 100], None, 20, Some((1000, 1e-10))).unwrap();
+        assert!(check(&a, &b, &x));
+        assert_eq!(history.len(), iter as usize + 1);
+
+        let (x, _, _) = gmres(&operator(&a), &b, vec![0.0; 100], Some(&ssor(&a, 1.0)), 10, None).unwrap();
+        assert!(check(&a, &b, &x));
+
+        let result = gmres(&operator(&a), &b, vec![0.0; 100], None, 5, Some((3, 1e-10)));
+        assert!(result.is_err());
+
+        // no restart length is taken as one
+        let (_, iter, _) = gmres(&operator(&a), &b, vec![0.0; 100], None, 0, Some((7, 1e-10))).unwrap_err();
+        assert_eq!(iter, 7);
+    }
+
+    #[test]
+    fn test_bicgstab() {
+        let a = convection_diffusion(100, 0.4);
+        let b: Vec<f64> = (0..100).map(|i| (i as f64).cos()).collect();
+        let (x, _, _) = bicgstab(&operator(&a), &b, vec![0.0; 100], None, None).unwrap();
+        assert!(check(&a, &b, &x));
+
+        let diag = (0..100).map(|i| a[i][i]).collect();
+        let (x, _, _) = bicgstab(&operator(&a), &b, vec![0.0; 100], Some(&jacobi(diag)), None).unwrap();
+        assert!(check(&a, &b, &x));
+    }
+}
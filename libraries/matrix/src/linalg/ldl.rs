@@ -0,0 +1,110 @@
+use super::{Matrix, AsMatrixViewMut, MatrixError, check_square};
+
+pub fn decomp(a: &mut impl AsMatrixViewMut<f64>) -> Result<Vec<f64>, MatrixError> {
+    // A <- L with unit diagonal, returns D such that A = L D L^T
+    // only the lower triangle of A is read, no pivoting is done
+    // fails with Singular if the j'th pivot vanishes relative to the size of A
+    let mut a = a.as_view_mut();
+    let n = a.num_rows;
+    check_square(n, a.num_cols)?;
+    let scale = a.iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+    let tol = f64::EPSILON * scale * n as f64;
+    let mut d = Vec::with_capacity(n);
+    for j in 0..n {
+        let mut dj = a[(j, j)];
+        for k in 0..j {
+            dj -= a[(j, k)] * a[(j, k)] * d[k];
+        }
+        if dj.abs() <= tol {
+            return Err(MatrixError::Singular {pivot: j})
+        }
+        d.push(dj);
+        a[(j, j)] = 1.0;
+        for i in j+1..n {
+            let mut sum = a[(i, j)];
+            for k in 0..j {
+                sum -= a[(i, k)] * a[(j, k)] * d[k];
+            }
+            a[(i, j)] = sum / dj;
+        }
+        for i in 0..j {  // clear the upper triangle
+            a[(i, j)] = 0.0;
+        }
+    }
+    return Ok(d)
+}
+
+pub fn solve(l: &Matrix<f64>, d: &Vec<f64>, b: &mut Matrix<f64>) {
+    // solves L D L^T x = b for every column of b, b <- x
+    let n = l.num_rows;
+    assert!(b.num_rows == n, "Non-compatible dimensions!");
+    for k in 0..b.num_cols {
+        let x = &mut b[k];
+        for i in 0..n {  // L y = b
+            for j in 0..i {
+                x[i] -= l[j][i] * x[j];
+            }
+        }
+        for i in 0..n {  // D z = y
+            x[i] /= d[i];
+        }
+        for i in (0..n).rev() {  // L^T x = z
+            for j in i+1..n {
+                x[i] -= l[i][j] * x[j];
+            }
+        }
+    }
+}
+
+pub fn log_determinant(d: &Vec<f64>) -> (f64, f64) {
+    // returns (log|det A|, sign of det A)
+    let log_det = d.iter().map(|x| x.abs().ln()).sum();
+    let sign = d.iter().fold(1.0, |sign, x| sign * x.signum());
+    return (log_det, sign)
+}
+
+pub fn inverse(l: &Matrix<f64>, d: &Vec<f64>) -> Matrix<f64> {
+    let mut result = Matrix::idty(l.num_rows);
+    solve(l, d, &mut result);
+    return result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::zip;
+
+    #[test]
+    fn test_decomp() {
+        let mut a = Matrix::new(vec![vec![4.0, 12.0, -16.0], vec![12.0, 37.0, -43.0], vec![-16.0, -43.0, 98.0]]);
+        let d = decomp(&mut a).unwrap();
+
+        assert_eq!(d, vec![4.0, 1.0, 9.0]);
+        assert!(
+            zip(
+                a.iter(),
+                [1.0, 3.0, -4.0, 0.0, 1.0, 5.0, 0.0, 0.0, 1.0]
+            ).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-15))
+        );
+    }
+
+    #[test]
+    fn test_indefinite() {
+        let a = Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 1.0]]);
+        let mut l = a.clone();
+        let d = decomp(&mut l).unwrap();
+        let (log_det, sign) = log_determinant(&d);
+        assert!((log_det - 3.0_f64.ln()).abs() < 1e-15 && sign == -1.0);
+
+        let a_inv = inverse(&l, &d);
+        assert!(
+            (&a * &a_inv - Matrix::idty(2)).iter().fold(true, |acc, item| acc && item.abs() < 1e-15)
+        );
+    }
+
+    #[test]
+    fn test_zero_pivot() {
+        let mut a = Matrix::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        assert_eq!(decomp(&mut a), Err(MatrixError::Singular {pivot: 0}));
+    }
+}
@@ -1,9 +1,18 @@
 use super::{qr, back_substitution};
 use super::Matrix;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    GramSchmidt,
+    Householder,  // column pivoted, gives the minimum-norm solution for rank-deficient fits
+}
+
 pub fn fit(fns: &Vec<&dyn Fn(f64) -> f64>, x: &Matrix<f64>, y: &Matrix<f64>, dy: &Matrix<f64>) -> (Matrix<f64>, Matrix<f64>) {
+    fit_with(fns, x, y, dy, Method::GramSchmidt)
+}
+
+pub fn fit_with(fns: &Vec<&dyn Fn(f64) -> f64>, x: &Matrix<f64>, y: &Matrix<f64>, dy: &Matrix<f64>, method: Method) -> (Matrix<f64>, Matrix<f64>) {
     let mut a = Matrix::zeros(x.num_rows, fns.len());
-    let mut r = Matrix::idty(a.num_cols);
     let mut b = Matrix::zeros(x.num_rows, 1);
     for k in 0..fns.len() { // build a matrix A
         let fk = fns[k];
@@ -15,7 +24,15 @@ pub fn fit(fns: &Vec<&dyn Fn(f64) -> f64>, x: &Matrix<f64>, y: &Matrix<f64>, dy:
     for i in 0..x.num_rows { // build b vector
         b[0][i] = y[0][i] / dy[0][i];
     }
-    
+
+    match method {
+        Method::GramSchmidt => gram_schmidt_fit(a, b),
+        Method::Householder => householder_fit(a, b),
+    }
+}
+
+fn gram_schmidt_fit(mut a: Matrix<f64>, mut b: Matrix<f64>) -> (Matrix<f64>, Matrix<f64>) {
+    let mut r = Matrix::idty(a.num_cols);
     qr::decomp(&mut a, &mut r); // QR decompose A
     b = a.transpose() * b;
     back_substitution(&r, &mut b); // solve linear equation R c = Q^T b
@@ -24,6 +41,45 @@ pub fn fit(fns: &Vec<&dyn Fn(f64) -> f64>, x: &Matrix<f64>, y: &Matrix<f64>, dy:
     return (b, sigma)
 }
 
+fn householder_fit(mut a: Matrix<f64>, mut b: Matrix<f64>) -> (Matrix<f64>, Matrix<f64>) {
+    // complete orthogonal decomposition A P = Q [S^T 0] Z^T, see e.g. Golub & Van Loan 5.5
+    let n = a.num_cols;
+    let (tau, perm, rank) = qr::householder_decomp(&mut a, true);
+    qr::householder_qt_times(&a, &tau, &mut b);
+
+    let mut t = Matrix::zeros(n, rank); // t = [R11 R12]^T
+    for i in 0..rank {
+        for j in i..n {
+            t[i][j] = a[j][i];
+        }
+    }
+    let (tau_t, _, _) = qr::householder_decomp(&mut t, false);
+    let z = qr::householder_q(&t, &tau_t);
+    let s = qr::householder_r(&t);
+
+    let mut w = Matrix::zeros(rank, 1); // solve S^T w = (Q^T b)_1..rank
+    for i in 0..rank {
+        let mut sum = b[0][i];
+        for j in 0..i {
+            sum -= s[i][j] * w[0][j];
+        }
+        w[0][i] = sum / s[i][i];
+    }
+    let y = &z * w;
+
+    let m = z * qr::inverse(&Matrix::idty(rank), &s).transpose(); // A^+ = P Z S^-T Q^T
+    let sigma_y = &m * m.transpose();
+    let mut c = Matrix::zeros(n, 1);
+    let mut sigma = Matrix::zeros(n, n);
+    for i in 0..n {
+        c[0][perm[i]] = y[0][i];
+        for j in 0..n {
+            sigma[perm[j]][perm[i]] = sigma_y[j][i];
+        }
+    }
+    return (c, sigma)
+}
+
 pub fn correlations(covariance: &mut Matrix<f64>) { // only updates upper triangular part
     covariance[0][0] = f64::sqrt(covariance[0][0]);
     for j in 1..covariance.num_cols {
@@ -55,4 +111,41 @@ mod tests {
             fit_cs.iter().enumerate().fold(true, |acc, (i, item)| acc && ((item-cs[i]).abs() < 1e-10))
         );
     }
+
+    #[test]
+    fn test_householder_fit() {
+        let cs = [4.0, -1.0, 2.0];
+        let f = |x| cs[0] + cs[1]*x + cs[2]*x*x;
+        let xs = Matrix::from_data(vec![0.0, 2.0, 5.0, 10.0], 4, 1);
+        let ys = Matrix::from_data(xs.iter().map(|x| f(x)).collect(), 4, 1);
+        let dys = Matrix::from_data(vec![0.01; 4], 4, 1);
+
+        let fs: Vec<&dyn Fn(f64) -> f64> = vec![&|_x| 1.0, &|x| x, &|x| x*x,];
+        let (fit_cs, sigma) = fit_with(&fs, &xs, &ys, &dys, Method::Householder);
+        let (_, gs_sigma) = fit(&fs, &xs, &ys, &dys);
+        assert!(
+            fit_cs.iter().enumerate().fold(true, |acc, (i, item)| acc && ((item-cs[i]).abs() < 1e-10))
+        );
+        assert!(
+            (sigma - gs_sigma).iter().fold(true, |acc, item| acc && item.abs() < 1e-12)
+        );
+    }
+
+    #[test]
+    fn test_rank_deficient_fit() {
+        let f = |x| 1.0 + 3.0*x;
+        let xs = Matrix::from_data(vec![0.0, 1.0, 2.0, 3.0], 4, 1);
+        let ys = Matrix::from_data(xs.iter().map(|x| f(x)).collect(), 4, 1);
+        let dys = Matrix::from_data(vec![0.1; 4], 4, 1);
+
+        // the last two functions are linearly dependent, the minimum-norm split of 3x is 0.6x + 1.2x
+        let fs: Vec<&dyn Fn(f64) -> f64> = vec![&|_x| 1.0, &|x| x, &|x| 2.0*x,];
+        let (fit_cs, sigma) = fit_with(&fs, &xs, &ys, &dys, Method::Householder);
+        assert!(
+            fit_cs.iter().zip([1.0, 0.6, 1.2]).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-12))
+        );
+        assert!(
+            sigma.iter().fold(true, |acc, item| acc && item.is_finite())
+        );
+    }
 }
\ No newline at end of file
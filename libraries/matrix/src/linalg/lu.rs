@@ -0,0 +1,182 @@
+use super::{Matrix, AsMatrixView, AsMatrixViewMut, MatrixError, check_square, back_substitution};
+use num_complex::ComplexFloat;
+
+// LU factorisation with partial pivoting for real and complex matrices alike, pivots are chosen by modulus.
+
+pub fn decomp<T: ComplexFloat<Real = f64>>(a: &mut impl AsMatrixViewMut<T>) -> Result<Vec<usize>, MatrixError> {
+    // A <- L U packed in place, with the unit diagonal of L left implicit
+    // returns the row permutation such that P A = L U, where row i of P A is row perm[i] of A
+    // a pivot that vanishes relative to the size of A is reported as Singular with its column index
+    let mut a = a.as_view_mut();
+    let n = a.num_rows;
+    check_square(n, a.num_cols)?;
+    let scale = a.iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+    let tol = f64::EPSILON * scale * n as f64;
+    let mut perm: Vec<usize> = (0..n).collect();
+
+    for k in 0..n {
+        let mut p = k;  // partial pivoting: largest element of column k on or below the diagonal
+        for i in k+1..n {
+            if a[(i, k)].abs() > a[(p, k)].abs() {p = i}
+        }
+        if a[(p, k)].abs() <= tol {
+            return Err(MatrixError::Singular {pivot: k})
+        }
+        if p != k {
+            perm.swap(k, p);
+            for j in 0..n {
+                let (akj, apj) = (a[(k, j)], a[(p, j)]);
+                a[(k, j)] = apj;
+                a[(p, j)] = akj;
+            }
+        }
+
+        let pivot = a[(k, k)];
+        for i in k+1..n {
+            a[(i, k)] = a[(i, k)] / pivot;
+        }
+        for j in k+1..n {
+            let akj = a[(k, j)];
+            if akj == T::zero() {continue}
+            for i in k+1..n {
+                a[(i, j)] = a[(i, j)] - a[(i, k)] * akj;
+            }
+        }
+    }
+    return Ok(perm)
+}
+
+pub fn solve<T: ComplexFloat<Real = f64>>(lu: &impl AsMatrixView<T>, perm: &Vec<usize>, b: &mut impl AsMatrixViewMut<T>) {
+    // solves A x = b for every column of b, b <- x
+    let lu = lu.as_view();
+    let mut b = b.as_view_mut();
+    let n = lu.num_rows;
+    assert!(b.num_rows == n, "Non-compatible dimensions!");
+    for k in 0..b.num_cols {
+        let y: Vec<T> = perm.iter().map(|&i| b[(i, k)]).collect();
+        for i in 0..n {b[(i, k)] = y[i]}
+        for i in 1..n {  // forward substitution with unit lower triangle
+            for j in 0..i {
+                b[(i, k)] = b[(i, k)] - lu[(i, j)] * b[(j, k)];
+            }
+        }
+    }
+    back_substitution(&lu, &mut b);  // with U
+}
+
+fn is_odd(perm: &Vec<usize>) -> bool {
+    // each cycle of length l in the permutation contributes l-1 transpositions
+    let mut visited = vec![false; perm.len()];
+    let mut odd = false;
+    for start in 0..perm.len() {
+        let mut i = start;
+        let mut len = 0;
+        while !visited[i] {
+            visited[i] = true;
+            i = perm[i];
+            len += 1;
+        }
+        if len > 0 && len % 2 == 0 {odd = !odd}
+    }
+    return odd
+}
+
+pub fn determinant<T: ComplexFloat<Real = f64>>(lu: &impl AsMatrixView<T>, perm: &Vec<usize>) -> T {
+    let lu = lu.as_view();
+    let det = (0..lu.num_rows).fold(T::one(), |prod, i| prod * lu[(i, i)]);
+    return if is_odd(perm) {-det} else {det}
+}
+
+pub fn inverse<T: ComplexFloat<Real = f64>>(lu: &impl AsMatrixView<T>, perm: &Vec<usize>) -> Matrix<T> {
+    let n = lu.as_view().num_rows;
+    let mut result = Matrix::from_fn(n, n, |i, j| if i == j {T::one()} else {T::zero()});
+    solve(lu, perm, &mut result);
+    return result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex;
+    use std::iter::zip;
+
+    #[test]
+    fn test_solve() {
+        let a = Matrix::new(vec![vec![2.0, 4.0, -2.0], vec![1.0, -6.0, 7.0], vec![1.0, 0.0, 2.0]]);
+        let mut lu = a.clone();
+        let perm = decomp(&mut lu).unwrap();
+        let mut b = Matrix::from_data(vec![5.0, -2.0, 9.0, 1.0, 0.0, 0.0], 3, 2);
+        solve(&lu, &perm, &mut b);
+
+        assert!(
+            zip(
+                (&a * &b).iter(),
+                [5.0, -2.0, 9.0, 1.0, 0.0, 0.0]
+            ).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-14))
+        );
+    }
+
+    #[test]
+    fn test_determinant() {
+        let mut a = Matrix::new(vec![vec![0.0, 1.0, 0.0], vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 3.0]]);
+        let perm = decomp(&mut a).unwrap();
+        assert!((determinant(&a, &perm) + 3.0).abs() < 1e-15);
+
+        let mut a = Matrix::new(vec![vec![1.0, 0.0, 3.0], vec![3.0, 2.0, 1.0], vec![1.0, 2.0, 0.0]]);
+        let perm = decomp(&mut a).unwrap();
+        assert!((determinant(&a, &perm) - 10.0).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let mut a = Matrix::new(vec![vec![1.0, 0.0, 3.0], vec![3.0, 2.0, 1.0], vec![1.0, 2.0, 0.0]]);
+        let perm = decomp(&mut a).unwrap();
+        let a_inv = inverse(&a, &perm);
+
+        assert!(
+            zip(
+                a_inv.iter(),
+                [-0.2, 0.6, -0.6, 0.1, -0.3, 0.8, 0.4, -0.2, 0.2]
+            ).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-15))
+        );
+    }
+
+    #[test]
+    fn test_singular() {
+        let mut a = Matrix::from_data((1..=9).map(|x| x as f64).collect(), 3, 3);
+        assert_eq!(decomp(&mut a), Err(MatrixError::Singular {pivot: 2}));
+        assert_eq!(decomp(&mut Matrix::<f64>::zeros(2, 3)), Err(MatrixError::NotSquare {shape: (2, 3)}));
+    }
+
+    #[test]
+    fn test_complex() {
+        let i = Complex::new(0.0, 1.0);
+        let one = Complex::new(1.0, 0.0);
+        let a = Matrix::new(vec![vec![2.0 * one, i, -one], vec![one + i, 3.0 * one, 0.5 * i], vec![4.0 * one, -i, one]]);
+        let mut lu = a.clone();
+        let perm = decomp(&mut lu).unwrap();
+        let product = &a * inverse(&lu, &perm);
+        assert!(product.iter().zip(Matrix::complex_idty(3).iter()).all(|(x, y)| (x - y).norm() < 1e-14));
+
+        // det of [[0, 1], [1, 2i]] is -1, with a row swap in the factorisation
+        let mut a = Matrix::new(vec![vec![Complex::new(0.0, 0.0), one], vec![one, 2.0 * i]]);
+        let perm = decomp(&mut a).unwrap();
+        assert_eq!(perm, vec![1, 0]);
+        assert!((determinant(&a, &perm) + one).norm() < 1e-15);
+    }
+
+    #[test]
+    fn test_view() {
+        // factorise the lower right block in place, leaving the rest untouched
+        let mut big = Matrix::zeros(4, 4);
+        let a = Matrix::new(vec![vec![1.0, 0.0, 3.0], vec![3.0, 2.0, 1.0], vec![1.0, 2.0, 0.0]]);
+        big.slice_mut(1..4, 1..4).copy_from(&a);
+        big.set(7.0, 0, 0);
+        let perm = decomp(&mut big.slice_mut(1..4, 1..4)).unwrap();
+
+        let mut lu = a.clone();
+        assert_eq!(decomp(&mut lu).unwrap(), perm);
+        assert_eq!(big.slice(1..4, 1..4), lu);
+        assert_eq!(big.col(0), Matrix::from_data(vec![7.0, 0.0, 0.0, 0.0], 4, 1));
+    }
+}
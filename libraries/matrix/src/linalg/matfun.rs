@@ -0,0 +1,263 @@
+use super::{Matrix, MatrixError, check_square};
+use super::{lu, schur};
+use super::eig::{jacobi_cyclic_with, JacobiOptions};
+
+// Matrix functions f(A). Symmetric matrices go through the eigendecomposition A = V D V^T,
+// general matrices through scaling and squaring with Pade approximants, on the real Schur form for log and sqrt.
+
+fn is_symmetric(a: &Matrix<f64>) -> bool {
+    a.is_symmetric(8.0 * f64::EPSILON * a.max_norm())
+}
+
+fn symmetric_apply(a: &Matrix<f64>, f: impl Fn(f64) -> Option<f64>, operation: &'static str) -> Result<Matrix<f64>, MatrixError> {
+    // V f(D) V^T, where f returns None outside its real domain
+    // Jacobi with the classic threshold runs to machine precision in the off-diagonal norm
+    let options = JacobiOptions {tolerance: f64::EPSILON, ..Default::default()};
+    let eig = jacobi_cyclic_with(a, &options);
+    if !eig.converged {return Err(MatrixError::NoConvergence {index: eig.sweeps})}
+    let v = eig.eigenvectors;
+    let mut v_f = v.clone();
+    for (j, &lambda) in eig.eigenvalues.iter().enumerate() {
+        let f_lambda = f(lambda).ok_or(MatrixError::NoRealSolution {operation: operation})?;
+        for x in v_f[j].iter_mut() {*x *= f_lambda}
+    }
+    return Ok(v_f * v.transpose())
+}
+
+fn solve(a: &Matrix<f64>, b: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // A^-1 B
+    let mut lu = a.clone();
+    let perm = lu::decomp(&mut lu)?;
+    let mut x = b.clone();
+    lu::solve(&lu, &perm, &mut x);
+    return Ok(x)
+}
+
+fn pade_exp(a: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // [13/13] Pade approximant of exp(A), accurate to double precision for ||A||_1 <= 5.37, see Higham (2005)
+    const B: [f64; 14] = [
+        64764752532480000.0, 32382376266240000.0, 7771770303897600.0, 1187353796428800.0,
+        129060195264000.0, 10559470521600.0, 670442572800.0, 33522128640.0,
+        1323241920.0, 40840800.0, 960960.0, 16380.0, 182.0, 1.0,
+    ];
+    let idty = Matrix::idty(a.num_rows);
+    let a2 = a * a;
+    let a4 = &a2 * &a2;
+    let a6 = &a4 * &a2;
+    let u = a * (&a6 * (&a6 * B[13] + &a4 * B[11] + &a2 * B[9]) + &a6 * B[7] + &a4 * B[5] + &a2 * B[3] + &idty * B[1]);
+    let v = &a6 * (&a6 * B[12] + &a4 * B[10] + &a2 * B[8]) + &a6 * B[6] + &a4 * B[4] + &a2 * B[2] + &idty * B[0];
+    return solve(&(&v - &u), &(v + u))
+}
+
+pub fn expm(a: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // matrix exponential, fails only if the Pade denominator is singular
+    check_square(a.num_rows, a.num_cols)?;
+    if is_symmetric(a) {
+        return symmetric_apply(a, |x| Some(x.exp()), "expm")
+    }
+    let theta = 5.371920351148152;
+    let norm = a.norm1();
+    let s = if norm <= theta {0} else {(norm / theta).log2().ceil() as i32};
+    let mut x = pade_exp(&(a / 2f64.powi(s)))?;
+    for _ in 0..s {
+        x = &x * &x;
+    }
+    return Ok(x)
+}
+
+fn blocks(t: &Matrix<f64>) -> Vec<(usize, usize)> {
+    // (start, size) of the 1 x 1 and 2 x 2 diagonal blocks of a real Schur form
+    let n = t.num_rows;
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let size = if i + 1 < n && t[i][i+1] != 0.0 {2} else {1};
+        result.push((i, size));
+        i += size;
+    }
+    return result
+}
+
+fn block_sqrt(t: &Matrix<f64>, (i, size): (usize, usize)) -> Result<Vec<f64>, MatrixError> {
+    // principal square root of a diagonal block, column-major
+    if size == 1 {
+        return if t[i][i] >= 0.0 {Ok(vec![t[i][i].sqrt()])} else {Err(MatrixError::NoRealSolution {operation: "sqrtm"})}
+    }
+    // eigenvalues theta +- i mu, and sqrt(B) = alpha I + (B - theta I) / (2 alpha) with alpha = Re sqrt(theta + i mu)
+    let (a, b, c, d) = (t[i][i], t[i][i+1], t[i+1][i], t[i+1][i+1]);
+    let theta = 0.5 * (a + d);
+    let mu = f64::sqrt(a * d - b * c - theta * theta);
+    let alpha = f64::sqrt(0.5 * (theta.hypot(mu) + theta));
+    return Ok(vec![alpha + (a - theta) / (2.0 * alpha), b / (2.0 * alpha), c / (2.0 * alpha), alpha + (d - theta) / (2.0 * alpha)])
+}
+
+fn quasi_triangular_sqrt(t: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // R with R^2 = T for T in real Schur form, block by block from the diagonal upwards, see Higham (1987)
+    // the off-diagonal blocks solve the Sylvester equations R_ii X + X R_jj = T_ij - sum_k R_ik R_kj
+    let n = t.num_rows;
+    let blocks = blocks(t);
+    let mut r = Matrix::zeros(n, n);
+    for (jb, &(j0, q)) in blocks.iter().enumerate() {
+        let root = block_sqrt(t, (j0, q))?;
+        for c in 0..q {
+            for s in 0..q {r[j0+c][j0+s] = root[c * q + s]}
+        }
+        for ib in (0..jb).rev() {
+            let (i0, p) = blocks[ib];
+            let mut rhs = Matrix::zeros(p * q, 1);
+            for c in 0..q {
+                for s in 0..p {
+                    let coupling: f64 = (i0+p..j0).map(|k| r[k][i0+s] * r[j0+c][k]).sum();
+                    rhs[0][c * p + s] = t[j0+c][i0+s] - coupling;
+                }
+            }
+            let mut kron = Matrix::zeros(p * q, p * q);  // I (x) R_ii + R_jj^T (x) I acting on vec(X)
+            for c in 0..q {
+                for s in 0..p {
+                    for u in 0..p {kron[c * p + u][c * p + s] += r[i0+u][i0+s]}
+                    for u in 0..q {kron[u * p + s][c * p + s] += r[j0+c][j0+u]}
+                }
+            }
+            let x = solve(&kron, &rhs)?;
+            for c in 0..q {
+                for s in 0..p {r[j0+c][i0+s] = x[0][c * p + s]}
+            }
+        }
+    }
+    return Ok(r)
+}
+
+pub fn sqrtm(a: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // principal square root, fails with NoRealSolution for negative real eigenvalues
+    check_square(a.num_rows, a.num_cols)?;
+    if is_symmetric(a) {
+        let tol = f64::EPSILON * a.num_rows as f64 * a.norm1();
+        return symmetric_apply(a, |x| if x >= -tol {Some(x.max(0.0).sqrt())} else {None}, "sqrtm")
+    }
+    let mut t = a.clone();
+    let z = schur::decomp(&mut t)?;
+    let r = quasi_triangular_sqrt(&t)?;
+    return Ok(&z * r * z.transpose())
+}
+
+fn gauss_legendre(m: usize) -> (Vec<f64>, Vec<f64>) {
+    // nodes and weights on [0, 1] by Newton iteration on the Legendre polynomial P_m
+    let (mut nodes, mut weights) = (Vec::with_capacity(m), Vec::with_capacity(m));
+    for k in 0..m {
+        let mut x = f64::cos(std::f64::consts::PI * (k as f64 + 0.75) / (m as f64 + 0.5));
+        let mut dp = 0.0;
+        for _ in 0..100 {
+            let (mut p0, mut p1) = (1.0, x);
+            for l in 2..=m {
+                (p0, p1) = (p1, ((2 * l - 1) as f64 * x * p1 - (l - 1) as f64 * p0) / l as f64);
+            }
+            dp = m as f64 * (x * p1 - p0) / (x * x - 1.0);
+            let dx = p1 / dp;
+            x -= dx;
+            if dx.abs() < f64::EPSILON {break}
+        }
+        nodes.push(0.5 * (1.0 + x));
+        weights.push(1.0 / ((1.0 - x * x) * dp * dp));
+    }
+    return (nodes, weights)
+}
+
+fn pade_log(t: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // [8/8] Pade approximant of log(I + X) with X = T - I in partial fractions,
+    // log(I + X) = sum_j w_j (I + t_j X)^-1 X with Gauss-Legendre nodes t_j and weights w_j on [0, 1]
+    let n = t.num_rows;
+    let x = t - Matrix::idty(n);
+    let (nodes, weights) = gauss_legendre(8);
+    let mut result = Matrix::zeros(n, n);
+    for (t_j, w_j) in nodes.into_iter().zip(weights) {
+        result += solve(&(Matrix::idty(n) + &x * t_j), &x)? * w_j;
+    }
+    return Ok(result)
+}
+
+pub fn logm(a: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // principal logarithm, fails with NoRealSolution for real eigenvalues that are not positive
+    // inverse scaling and squaring: log(A) = 2^k log(A^(1/2^k)) with square roots taken until A^(1/2^k) is close to I
+    check_square(a.num_rows, a.num_cols)?;
+    if is_symmetric(a) {
+        return symmetric_apply(a, |x| if x > 0.0 {Some(x.ln())} else {None}, "logm")
+    }
+    let mut t = a.clone();
+    let z = schur::decomp(&mut t)?;
+    for (i, size) in blocks(&t) {
+        if size == 1 && t[i][i] <= 0.0 {return Err(MatrixError::NoRealSolution {operation: "logm"})}
+    }
+    let mut k = 0;
+    while (&t - Matrix::idty(t.num_rows)).norm1() > 0.25 {
+        if k == 64 {return Err(MatrixError::NoConvergence {index: k})}
+        t = quasi_triangular_sqrt(&t)?;
+        k += 1;
+    }
+    let l = pade_log(&t)? * 2f64.powi(k as i32);
+    return Ok(&z * l * z.transpose())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_diff(a: &Matrix<f64>, b: &Matrix<f64>) -> f64 {
+        (a - b).max_norm()
+    }
+
+    fn nonsymmetric() -> Matrix<f64> {
+        // eigenvalues 1, 2 and 3 +- 2i
+        let mut a = Matrix::new(vec![vec![1.0, 0.0, 0.0, 0.0], vec![0.5, 2.0, 0.0, 0.0], vec![1.0, 0.0, 3.0, -2.0], vec![0.0, 1.0, 2.0, 3.0]]);
+        let q = Matrix::new(vec![vec![0.5, 0.5, 0.5, 0.5], vec![0.5, -0.5, 0.5, -0.5], vec![0.5, 0.5, -0.5, -0.5], vec![0.5, -0.5, -0.5, 0.5]]);
+        a = &q * a * &q;
+        return a
+    }
+
+    #[test]
+    fn test_expm() {
+        // rotation generator, exp(t J) is a rotation by t
+        let t: f64 = 10.0;
+        let j = Matrix::new(vec![vec![0.0, t], vec![-t, 0.0]]);
+        let rotation = Matrix::new(vec![vec![t.cos(), t.sin()], vec![-t.sin(), t.cos()]]);
+        assert!(max_diff(&expm(&j).unwrap(), &rotation) < 1e-13);
+
+        // nilpotent, exp(N) = I + N + N^2/2
+        let n = Matrix::new(vec![vec![0.0, 0.0, 0.0], vec![1.0, 0.0, 0.0], vec![2.0, 3.0, 0.0]]);
+        assert!(max_diff(&expm(&n).unwrap(), &(Matrix::idty(3) + &n + &n * &n * 0.5)) < 1e-15);
+
+        let s = Matrix::new(vec![vec![2.0, 1.0], vec![1.0, 2.0]]);  // eigenvalues 1 and 3
+        let (e1, e3) = (1f64.exp(), 3f64.exp());
+        let exact = Matrix::new(vec![vec![e3 + e1, e3 - e1], vec![e3 - e1, e3 + e1]]) * 0.5;
+        assert!(max_diff(&expm(&s).unwrap(), &exact) < 1e-13);
+    }
+
+    #[test]
+    fn test_sqrtm() {
+        let a = nonsymmetric();
+        let r = sqrtm(&a).unwrap();
+        assert!(max_diff(&(&r * &r), &a) < 1e-13);
+
+        let s = Matrix::new(vec![vec![4.0, 1.0, 0.0], vec![1.0, 3.0, 1.0], vec![0.0, 1.0, 2.0]]);
+        let r = sqrtm(&s).unwrap();
+        assert!(max_diff(&(&r * &r), &s) < 1e-13);
+
+        let negative = Matrix::new(vec![vec![-1.0, 0.0], vec![1.0, 2.0]]);
+        assert_eq!(sqrtm(&negative), Err(MatrixError::NoRealSolution {operation: "sqrtm"}));
+    }
+
+    #[test]
+    fn test_logm() {
+        let a = nonsymmetric();
+        let l = logm(&a).unwrap();
+        assert!(max_diff(&expm(&l).unwrap(), &a) < 1e-12);
+
+        let s = Matrix::new(vec![vec![4.0, 1.0, 0.0], vec![1.0, 3.0, 1.0], vec![0.0, 1.0, 2.0]]);
+        assert!(max_diff(&expm(&logm(&s).unwrap()).unwrap(), &s) < 1e-13);
+
+        let rotation = Matrix::new(vec![vec![0.6, 0.8], vec![-0.8, 0.6]]);
+        let l = logm(&rotation).unwrap();
+        assert!(max_diff(&l, &Matrix::new(vec![vec![0.0, 0.8f64.atan2(0.6)], vec![-0.8f64.atan2(0.6), 0.0]])) < 1e-14);
+
+        assert_eq!(logm(&Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 0.0]])), Err(MatrixError::NoRealSolution {operation: "logm"}));
+    }
+}
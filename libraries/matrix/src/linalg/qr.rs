@@ -30,6 +30,115 @@ pub fn inverse(q: &Matrix<f64>, r: &Matrix<f64>) -> Matrix<f64> {
     return result;
 }
 
+pub fn householder_decomp(a: &mut Matrix<f64>, pivoting: bool) -> (Vec<f64>, Vec<usize>, usize) {
+    // A <- R in the upper triangle and the Householder vectors below the diagonal (unit first element implicit)
+    // returns (tau, perm, rank) such that A P = Q R, where column i of A P is column perm[i] of A
+    // and Q = H_0 H_1 ... with H_k = I - tau_k v_k v_k^T
+    // with pivoting the column of largest remaining norm is moved to the front at every step
+    let (m, n) = (a.num_rows, a.num_cols);
+    let kmax = usize::min(m, n);
+    let mut tau = Vec::with_capacity(kmax);
+    let mut perm: Vec<usize> = (0..n).collect();
+
+    for k in 0..kmax {
+        if pivoting {
+            let col_norm = |col: &[f64]| col[k..].iter().map(|x| x*x).sum::<f64>();
+            let mut p = k;
+            let mut max_norm = col_norm(&a[k]);
+            for j in k+1..n {
+                let norm = col_norm(&a[j]);
+                if norm > max_norm {
+                    (p, max_norm) = (j, norm);
+                }
+            }
+            if p != k {
+                perm.swap(k, p);
+                for i in 0..m {
+                    let (aik, aip) = (a[k][i], a[p][i]);
+                    a[k][i] = aip;
+                    a[p][i] = aik;
+                }
+            }
+        }
+
+        let alpha = a[k][k];
+        let norm = f64::sqrt(a[k][k..].iter().map(|x| x*x).sum());
+        if norm == 0.0 {
+            tau.push(0.0);
+            continue
+        }
+        let beta = if alpha > 0.0 {-norm} else {norm};
+        tau.push((beta - alpha) / beta);
+        for i in k+1..m {
+            a[k][i] /= alpha - beta;
+        }
+        a[k][k] = beta;
+
+        for j in k+1..n {  // apply H_k to the remaining columns
+            let mut w = a[j][k];
+            for i in k+1..m {
+                w += a[k][i] * a[j][i];
+            }
+            w *= tau[k];
+            a[j][k] -= w;
+            for i in k+1..m {
+                a[j][i] -= w * a[k][i];
+            }
+        }
+    }
+
+    let tol = f64::EPSILON * usize::max(m, n) as f64 * (0..kmax).fold(0.0, |max: f64, i| max.max(a[i][i].abs()));
+    let rank = (0..kmax).filter(|&i| a[i][i].abs() > tol).count();
+    return (tau, perm, rank)
+}
+
+fn apply_householder(qr: &Matrix<f64>, tau: &Vec<f64>, k: usize, b: &mut [f64]) {
+    // b <- H_k b
+    let mut w = b[k];
+    for i in k+1..qr.num_rows {
+        w += qr[k][i] * b[i];
+    }
+    w *= tau[k];
+    b[k] -= w;
+    for i in k+1..qr.num_rows {
+        b[i] -= w * qr[k][i];
+    }
+}
+
+pub fn householder_qt_times(qr: &Matrix<f64>, tau: &Vec<f64>, b: &mut Matrix<f64>) {
+    // b <- Q^T b for every column of b without forming Q
+    for j in 0..b.num_cols {
+        for k in 0..tau.len() {
+            apply_householder(qr, tau, k, &mut b[j]);
+        }
+    }
+}
+
+pub fn householder_q(qr: &Matrix<f64>, tau: &Vec<f64>) -> Matrix<f64> {
+    // forms the first min(m, n) columns of Q explicitly
+    let kmax = tau.len();
+    let mut q = Matrix::zeros(qr.num_rows, kmax);
+    for j in 0..kmax {
+        q[j][j] = 1.0;
+        for k in (0..=j).rev() {
+            apply_householder(qr, tau, k, &mut q[j]);
+        }
+    }
+    return q
+}
+
+pub fn householder_r(qr: &Matrix<f64>) -> Matrix<f64> {
+    // extracts the upper triangular min(m, n) x n factor R
+    let kmax = usize::min(qr.num_rows, qr.num_cols);
+    let mut r = Matrix::zeros(kmax, qr.num_cols);
+    for j in 0..qr.num_cols {
+        for i in 0..usize::min(j + 1, kmax) {
+            r[j][i] = qr[j][i];
+        }
+    }
+    return r
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +178,50 @@ mod tests {
             ).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-15))
         );
     }
+
+    #[test]
+    fn test_householder_decomp() {
+        let a = Matrix::new(vec![vec![2.0, 2.0, 1.0], vec![3.0, 4.0, 1.0]]);
+        let mut qr = a.clone();
+        let (tau, perm, rank) = householder_decomp(&mut qr, false);
+        let q = householder_q(&qr, &tau);
+        let r = householder_r(&qr);
+
+        assert_eq!(perm, vec![0, 1]);
+        assert_eq!(rank, 2);
+        assert!(
+            (&q * &r - &a).iter().fold(true, |acc, item| acc && item.abs() < 1e-15)
+        );
+        assert!(
+            (q.transpose() * &q - Matrix::idty(2)).iter().fold(true, |acc, item| acc && item.abs() < 1e-15)
+        );
+
+        let mut b = Matrix::from_data(vec![1.0, 2.0, 3.0], 3, 1);
+        let qtb = q.transpose() * &b;
+        householder_qt_times(&qr, &tau, &mut b);
+        assert!(
+            zip(b.iter(), qtb.iter()).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-15))
+        );
+    }
+
+    #[test]
+    fn test_householder_rank() {
+        let a = Matrix::new(vec![vec![1.0, 2.0, 3.0, 4.0], vec![2.0, 4.0, 6.0, 8.0], vec![1.0, 0.0, 1.0, 0.0]]);
+        let mut qr = a.clone();
+        let (tau, perm, rank) = householder_decomp(&mut qr, true);
+        assert_eq!(rank, 2);
+        assert_eq!(perm[0], 1);
+
+        let q = householder_q(&qr, &tau);
+        let r = householder_r(&qr);
+        let qr = q * r;
+        for (i, &j) in perm.iter().enumerate() {
+            assert!(
+                zip(qr[i].iter(), a[j].iter()).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-14))
+            );
+        }
+
+        let r = householder_r(&Matrix::zeros(0, 3));
+        assert_eq!((r.num_rows, r.num_cols), (0, 3));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,286 @@
+use super::{Matrix, AsMatrixView, MatrixError, check_square};
+use super::eig::{hessenberg, times_j, j_times};
+use num_complex::Complex;
+
+fn reflector(x: &[f64]) -> Option<(Vec<f64>, f64)> {
+    // Householder vector v and beta such that (I - beta v v^T) x is parallel to e_1
+    let alpha = f64::sqrt(x.iter().map(|xi| xi*xi).sum());
+    if alpha == 0.0 {return None}
+    let mut v = x.to_vec();
+    v[0] += if x[0] < 0.0 {-alpha} else {alpha};
+    let beta = 2.0 / v.iter().map(|vi| vi*vi).sum::<f64>();
+    return Some((v, beta))
+}
+
+fn reflect_rows(h: &mut Matrix<f64>, v: &[f64], beta: f64, k: usize, cols: std::ops::Range<usize>) {
+    // rows k..k+len(v) <- (I - beta v v^T) rows
+    for j in cols {
+        let w = beta * (0..v.len()).map(|i| v[i] * h[j][k+i]).sum::<f64>();
+        for i in 0..v.len() {
+            h[j][k+i] -= w * v[i];
+        }
+    }
+}
+
+fn reflect_cols(h: &mut Matrix<f64>, v: &[f64], beta: f64, k: usize, rows: std::ops::Range<usize>) {
+    // columns k..k+len(v) <- columns (I - beta v v^T)
+    for i in rows {
+        let w = beta * (0..v.len()).map(|c| v[c] * h[k+c][i]).sum::<f64>();
+        for c in 0..v.len() {
+            h[k+c][i] -= w * v[c];
+        }
+    }
+}
+
+fn francis_step(h: &mut Matrix<f64>, z: &mut Matrix<f64>, l: usize, hi: usize, exceptional: bool) {
+    // one implicit double shift QR step on the active window l..=hi, which must be at least 3 x 3
+    // the transformations are applied to all of H and Z to keep the full Schur form
+    let n = h.num_rows;
+    let (mut s, mut t) = (h[hi-1][hi-1] + h[hi][hi], h[hi-1][hi-1] * h[hi][hi] - h[hi][hi-1] * h[hi-1][hi]);
+    if exceptional {  // ad hoc shift to break cycles, as in EISPACK hqr
+        let w = h[hi-1][hi].abs() + h[hi-2][hi-1].abs();
+        s = 1.5 * w;
+        t = w * w;
+    }
+    let mut x = h[l][l] * h[l][l] + h[l+1][l] * h[l][l+1] - s * h[l][l] + t;
+    let mut y = h[l][l+1] * (h[l][l] + h[l+1][l+1] - s);
+    let mut w = h[l][l+1] * h[l+1][l+2];
+
+    for k in l..hi-1 {  // chase the bulge down the subdiagonal
+        if let Some((v, beta)) = reflector(&[x, y, w]) {
+            reflect_rows(h, &v, beta, k, usize::max(l, k.saturating_sub(1))..n);
+            reflect_cols(h, &v, beta, k, 0..usize::min(k+4, hi+1));
+            reflect_cols(z, &v, beta, k, 0..n);
+        }
+        if k > l {
+            h[k-1][k+1] = 0.0;
+            h[k-1][k+2] = 0.0;
+        }
+        x = h[k][k+1];
+        y = h[k][k+2];
+        if k + 3 <= hi {w = h[k][k+3]}
+    }
+    if let Some((v, beta)) = reflector(&[x, y]) {
+        reflect_rows(h, &v, beta, hi-1, hi-2..n);
+        reflect_cols(h, &v, beta, hi-1, 0..hi+1);
+        reflect_cols(z, &v, beta, hi-1, 0..n);
+    }
+    h[hi-2][hi] = 0.0;
+}
+
+fn split_block(h: &mut Matrix<f64>, z: &mut Matrix<f64>, k: usize) {
+    // rotates a converged 2 x 2 block at k, k+1 with real eigenvalues to upper triangular form
+    let (a, b, c, d) = (h[k][k], h[k+1][k], h[k][k+1], h[k+1][k+1]);
+    if c == 0.0 {return}
+    let p = 0.5 * (a - d);
+    let disc = p * p + b * c;
+    if disc < 0.0 {return}  // complex conjugate pair, the block stays
+    let lambda = d + p + if p < 0.0 {-disc.sqrt()} else {disc.sqrt()};
+    let phi = f64::atan2(c, lambda - d);  // direction of the eigenvector (lambda - d, c)
+    j_times(h, k, k+1, phi);
+    times_j(h, k, k+1, -phi);
+    times_j(z, k, k+1, -phi);
+    h[k][k+1] = 0.0;
+}
+
+pub fn decomp(a: &mut Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // puts A to real Schur form A <- T by Francis double shift QR iteration on the Hessenberg form
+    // T is quasi upper triangular with 2 x 2 diagonal blocks only for complex conjugate pairs
+    // returns orthogonal transformation matrix such that A = Z T Z^T
+    // fails with NoConvergence if the eigenvalue at index did not converge, and with Empty for a 0 x 0 matrix
+    let n = a.num_rows;
+    check_square(n, a.num_cols)?;
+    if n == 0 {
+        return Err(MatrixError::Empty)
+    }
+    let mut z = hessenberg(a);
+    let norm = a.iter().fold(0.0, |sum, x| sum + x.abs());
+    let max_iter = 30 * n;
+    let (mut hi, mut iter, mut total_iter) = (n.saturating_sub(1), 0, 0);
+
+    while hi > 0 {
+        let mut l = hi;  // look for a negligible subdiagonal element
+        while l > 0 {
+            let mut s = a[l-1][l-1].abs() + a[l][l].abs();
+            if s == 0.0 {s = norm}
+            if a[l-1][l].abs() < f64::EPSILON * s {
+                a[l-1][l] = 0.0;
+                break
+            }
+            l -= 1;
+        }
+
+        if l == hi {  // 1 x 1 block converged
+            hi -= 1;
+            iter = 0;
+        } else if l + 1 == hi {  // 2 x 2 block converged
+            split_block(a, &mut z, l);
+            hi = hi.saturating_sub(2);
+            iter = 0;
+        } else {
+            iter += 1;
+            total_iter += 1;
+            if total_iter > max_iter {
+                return Err(MatrixError::NoConvergence {index: hi})
+            }
+            francis_step(a, &mut z, l, hi, iter % 10 == 0);
+        }
+    }
+    return Ok(z)
+}
+
+pub fn eigenvalues(t: &Matrix<f64>) -> Vec<Complex<f64>> {
+    // reads the eigenvalues off the diagonal of a real Schur form
+    // a complex conjugate pair is ordered with the positive imaginary part first
+    let n = t.num_rows;
+    let mut result = Vec::with_capacity(n);
+    let mut i = 0;
+    while i < n {
+        if i + 1 < n && t[i][i+1] != 0.0 {
+            let (a, b, c, d) = (t[i][i], t[i+1][i], t[i][i+1], t[i+1][i+1]);
+            let p = 0.5 * (a - d);
+            let im = f64::sqrt(-(p * p + b * c));
+            result.push(Complex::new(d + p, im));
+            result.push(Complex::new(d + p, -im));
+            i += 2;
+        } else {
+            result.push(Complex::new(t[i][i], 0.0));
+            i += 1;
+        }
+    }
+    return result
+}
+
+pub fn eigenvectors(t: &Matrix<f64>, z: &Matrix<f64>, lambdas: &Vec<Complex<f64>>) -> Matrix<Complex<f64>> {
+    // eigenvectors of A = Z T Z^T as normalised columns, matching the order of eigenvalues(T)
+    // solves (T - lambda I) y = 0 by back substitution and transforms back x = Z y
+    let n = t.num_rows;
+    let zero = Complex::new(0.0, 0.0);
+    let norm = t.iter().fold(0.0, |sum, x| sum + x.abs());
+    let small = Complex::new(f64::EPSILON * norm, 0.0);
+    let mut result = Matrix::from_data(vec![zero; n * n], n, n);
+
+    let mut k = 0;
+    while k < n {
+        let lambda = lambdas[k];
+        let mut y = vec![zero; n];
+        let end;
+        if lambda.im != 0.0 {
+            end = k + 1;
+            y[k+1] = Complex::new(1.0, 0.0);
+            y[k] = -t[k+1][k] / (t[k][k] - lambda);
+        } else {
+            end = k;
+            y[k] = Complex::new(1.0, 0.0);
+        }
+        let row = |i: usize, y: &Vec<Complex<f64>>| (i+1..=end).fold(zero, |sum, j| sum + y[j] * t[j][i]);
+
+        let mut i = k;
+        while i > 0 {
+            if i >= 2 && t[i-2][i-1] != 0.0 {  // 2 x 2 block in rows i-2, i-1
+                let (r1, r2) = (row(i-2, &y), row(i-1, &y));
+                let (m00, m01) = (t[i-2][i-2] - lambda, Complex::new(t[i-1][i-2], 0.0));
+                let (m10, m11) = (Complex::new(t[i-2][i-1], 0.0), t[i-1][i-1] - lambda);
+                let mut det = m00 * m11 - m01 * m10;
+                if det == zero {det = small}
+                y[i-2] = (-r1 * m11 + r2 * m01) / det;
+                y[i-1] = (-r2 * m00 + r1 * m10) / det;
+                i -= 2;
+            } else {
+                let r = row(i-1, &y);
+                let mut d = t[i-1][i-1] - lambda;
+                if d == zero {d = small}
+                y[i-1] = -r / d;
+                i -= 1;
+            }
+        }
+
+        let mut x: Vec<Complex<f64>> = (0..n).map(|r| (0..=end).fold(zero, |sum, j| sum + y[j] * z[j][r])).collect();
+        let x_norm = f64::sqrt(x.iter().map(|xi| xi.norm_sqr()).sum());
+        for xi in x.iter_mut() {*xi = *xi / x_norm}
+
+        if lambda.im != 0.0 {
+            result[k+1].clone_from_slice(&x.iter().map(|xi| xi.conj()).collect::<Vec<Complex<f64>>>());
+        }
+        result[k].clone_from_slice(&x);
+        k = end + 1;
+    }
+    return result
+}
+
+pub fn eig(a: &impl AsMatrixView<f64>, vectors: bool) -> Result<(Vec<Complex<f64>>, Option<Matrix<Complex<f64>>>), MatrixError> {
+    // eigenvalues and optionally eigenvectors of a general real matrix
+    let mut t = a.as_view().to_matrix();
+    let z = decomp(&mut t)?;
+    let lambdas = eigenvalues(&t);
+    let v = if vectors {Some(eigenvectors(&t, &z, &lambdas))} else {None};
+    return Ok((lambdas, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn residual(a: &Matrix<f64>, lambda: Complex<f64>, x: &[Complex<f64>]) -> f64 {
+        let n = a.num_rows;
+        (0..n).map(|i| {
+            let ax = (0..n).fold(Complex::new(0.0, 0.0), |sum, j| sum + x[j] * a[j][i]);
+            (ax - lambda * x[i]).norm()
+        }).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_rotation() {
+        let a = Matrix::from_data(vec![0.0, 1.0, -1.0, 0.0], 2, 2);
+        let (lambdas, v) = eig(&a, true).unwrap();
+        assert_eq!(lambdas, vec![Complex::new(0.0, 1.0), Complex::new(0.0, -1.0)]);
+        let v = v.unwrap();
+        for k in 0..2 {
+            assert!(residual(&a, lambdas[k], &v[k]) < 1e-15);
+        }
+
+        assert_eq!(eig(&Matrix::<f64>::zeros(0, 0), true), Err(MatrixError::Empty));
+        assert_eq!(decomp(&mut Matrix::zeros(0, 0)), Err(MatrixError::Empty));
+    }
+
+    #[test]
+    fn test_companion() {
+        // companion matrix of x^4 - 3x^3 + 3x^2 - 3x + 2 = (x - 1)(x - 2)(x^2 + 1)
+        let a = Matrix::new(vec![
+            vec![0.0, 1.0, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 0.0, 1.0],
+            vec![-2.0, 3.0, -3.0, 3.0],
+        ]);
+        let mut t = a.clone();
+        let z = decomp(&mut t).unwrap();
+        assert!(
+            (&z * &t * z.transpose() - &a).iter().fold(true, |acc, item| acc && item.abs() < 1e-13)
+        );
+        for j in 0..4 {
+            for i in j+2..4 {
+                assert_eq!(t[j][i], 0.0);
+            }
+        }
+
+        let mut lambdas = eigenvalues(&t);
+        lambdas.sort_by(|x, y| (x.re, x.im).partial_cmp(&(y.re, y.im)).unwrap());
+        let exact = [Complex::new(0.0, -1.0), Complex::new(0.0, 1.0), Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)];
+        for (lambda, test) in lambdas.iter().zip(exact.iter()) {
+            assert!((lambda - test).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_eigenvectors() {
+        let data: Vec<f64> = (0..36).map(|k| ((7 * k * k + 3 * k) % 11) as f64 - 5.0).collect();
+        let a = Matrix::from_data(data, 6, 6);
+        let (lambdas, v) = eig(&a, true).unwrap();
+        let v = v.unwrap();
+        for k in 0..6 {
+            assert!(residual(&a, lambdas[k], &v[k]) < 1e-12);
+        }
+        let trace: f64 = (0..6).map(|i| a[i][i]).sum();
+        assert!((lambdas.iter().map(|l| l.re).sum::<f64>() - trace).abs() < 1e-12);
+    }
+}
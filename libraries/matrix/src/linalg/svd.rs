@@ -0,0 +1,182 @@
+use super::{Matrix, AsMatrixView, MatrixError};
+use super::eig::{times_j, MAX_SWEEPS};
+
+pub fn decomp(a: &impl AsMatrixView<f64>) -> Result<(Matrix<f64>, Vec<f64>, Matrix<f64>), MatrixError> {
+    // thin singular value decomposition by one-sided Jacobi rotations
+    // returns (U, S, V) such that A = U diag(S) V^T with S sorted in descending order
+    // U is m x k and V is n x k with k = min(m, n)
+    // fails with NoConvergence if column index is still rotated in sweep MAX_SWEEPS, e.g. for NaN elements
+    let a = a.as_view();
+    if a.num_rows < a.num_cols {
+        let (u, s, v) = decomp(&a.transpose())?;
+        return Ok((v, s, u))
+    }
+    let n = a.num_cols;
+    let mut u = a.to_matrix();
+    let mut v = Matrix::<f64>::idty(n);
+    // columns below this squared norm are rounding noise and are left alone
+    let negligible = f64::EPSILON * f64::EPSILON * a.iter().map(|x| x*x).sum::<f64>();
+
+    let mut sweeps = 0;
+    loop {
+        let mut rotated: Option<usize> = None;
+        for p in 0..n {
+            for q in p+1..n {
+                // rotate columns p and q of U into orthogonality, i.e. one Jacobi step on A^T A
+                let (mut app, mut aqq, mut apq) = (0.0, 0.0, 0.0);
+                for (x, y) in u[p].iter().zip(u[q].iter()) {
+                    app += x * x;
+                    aqq += y * y;
+                    apq += x * y;
+                }
+                if app <= negligible || aqq <= negligible {continue}
+                if apq.abs() <= f64::EPSILON * f64::sqrt(app * aqq) {continue}
+                let theta = 0.5 * f64::atan2(2.0 * apq, aqq - app);
+                times_j(&mut u, p, q, theta);
+                times_j(&mut v, p, q, theta);
+                rotated.get_or_insert(p);
+            }
+        }
+        sweeps += 1;
+        match rotated {
+            None => break,
+            Some(p) if sweeps == MAX_SWEEPS => return Err(MatrixError::NoConvergence {index: p}),
+            Some(_) => {},
+        }
+    }
+
+    let mut s: Vec<f64> = (0..n).map(|j| f64::sqrt(u[j].iter().map(|x| x*x).sum())).collect();
+    for j in 0..n {
+        if s[j] > 0.0 {
+            for x in u[j].iter_mut() {*x /= s[j]}
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| s[j].total_cmp(&s[i]));
+    let (mut u_sorted, mut v_sorted) = (Matrix::zeros(u.num_rows, n), Matrix::zeros(n, n));
+    for (j, &k) in order.iter().enumerate() {
+        u_sorted[j].clone_from_slice(&u[k]);
+        v_sorted[j].clone_from_slice(&v[k]);
+    }
+    s = order.iter().map(|&k| s[k]).collect();
+    return Ok((u_sorted, s, v_sorted))
+}
+
+fn tolerance(a: &impl AsMatrixView<f64>, s: &Vec<f64>) -> f64 {
+    // singular values below this are treated as zero
+    let a = a.as_view();
+    f64::EPSILON * usize::max(a.num_rows, a.num_cols) as f64 * s.first().unwrap_or(&0.0)
+}
+
+pub fn pseudo_inverse(a: &impl AsMatrixView<f64>) -> Result<Matrix<f64>, MatrixError> {
+    let (u, s, v) = decomp(a)?;
+    let tol = tolerance(a, &s);
+    let mut v_s = v;  // V S^+
+    for j in 0..s.len() {
+        let inv = if s[j] > tol {1.0 / s[j]} else {0.0};
+        for x in v_s[j].iter_mut() {*x *= inv}
+    }
+    return Ok(v_s * u.transpose())
+}
+
+pub fn rank(a: &impl AsMatrixView<f64>) -> Result<usize, MatrixError> {
+    let (_, s, _) = decomp(a)?;
+    let tol = tolerance(a, &s);
+    return Ok(s.iter().filter(|&&x| x > tol).count())
+}
+
+pub fn norm2(a: &impl AsMatrixView<f64>) -> Result<f64, MatrixError> {
+    let (_, s, _) = decomp(a)?;
+    return Ok(s.first().cloned().unwrap_or(0.0))
+}
+
+pub fn condition_number(a: &impl AsMatrixView<f64>) -> Result<f64, MatrixError> {
+    let (_, s, _) = decomp(a)?;
+    return Ok(match s.last() {
+        Some(&min) if min > 0.0 => s[0] / min,
+        _ => f64::INFINITY,
+    })
+}
+
+pub fn null_space(a: &impl AsMatrixView<f64>) -> Result<Matrix<f64>, MatrixError> {
+    // orthonormal basis of the null space of A as columns
+    let a = a.as_view();
+    let n = a.num_cols;
+    let mut padded = Matrix::zeros(usize::max(a.num_rows, n), n);  // ensure a full V
+    padded.slice_mut(0..a.num_rows, 0..n).copy_from(&a);
+    let (_, s, v) = decomp(&padded)?;
+    let tol = tolerance(&a, &s);
+    let rank = s.iter().filter(|&&x| x > tol).count();
+    let mut result = Matrix::zeros(n, n - rank);
+    for j in rank..n {
+        result[j - rank].clone_from_slice(&v[j]);
+    }
+    return Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::zip;
+
+    #[test]
+    fn test_decomp() {
+        let a = Matrix::new(vec![vec![3.0, 2.0], vec![2.0, 3.0], vec![2.0, -2.0]]);  // 2 x 3
+        let (u, s, v) = decomp(&a).unwrap();
+        assert!(
+            zip(s.iter(), [5.0, 3.0]).fold(true, |acc, (item, test)| acc && ((item-test).abs() < 1e-14))
+        );
+
+        let mut us = u.clone();
+        for j in 0..s.len() {
+            for x in us[j].iter_mut() {*x *= s[j]}
+        }
+        assert!(
+            (us * v.transpose() - &a).iter().fold(true, |acc, item| acc && item.abs() < 1e-14)
+        );
+        assert!(
+            (v.transpose() * &v - Matrix::idty(2)).iter().fold(true, |acc, item| acc && item.abs() < 1e-14)
+        );
+    }
+
+    #[test]
+    fn test_pseudo_inverse() {
+        let a = Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]]);  // rank 1
+        let a_pinv = pseudo_inverse(&a).unwrap();
+        assert!(
+            (&a * &a_pinv * &a - &a).iter().fold(true, |acc, item| acc && item.abs() < 1e-13)
+        );
+        assert!(
+            (&a_pinv * &a * &a_pinv - &a_pinv).iter().fold(true, |acc, item| acc && item.abs() < 1e-13)
+        );
+        assert_eq!(rank(&a), Ok(1));
+    }
+
+    #[test]
+    fn test_norm_and_condition_number() {
+        let a = Matrix::from_data(vec![2.0, 0.0, 0.0, -0.5], 2, 2);
+        assert!((norm2(&a).unwrap() - 2.0).abs() < 1e-15);
+        assert!((condition_number(&a).unwrap() - 4.0).abs() < 1e-15);
+        assert_eq!(condition_number(&Matrix::zeros(2, 2)), Ok(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_null_space() {
+        let a = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]]);  // 2 x 3
+        let ns = null_space(&a).unwrap();
+        assert_eq!((ns.num_rows, ns.num_cols), (3, 1));
+        assert!(
+            (&a * &ns).iter().fold(true, |acc, item| acc && item.abs() < 1e-14)
+        );
+        assert!((ns[0].iter().map(|x| x*x).sum::<f64>() - 1.0).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_no_convergence() {
+        let mut a = Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0], vec![7.0, 8.0, 10.0]]);
+        a[1][1] = f64::NAN;
+        assert_eq!(decomp(&a).err(), Some(MatrixError::NoConvergence {index: 0}));
+        assert!(rank(&a).is_err() && norm2(&a).is_err());
+    }
+}
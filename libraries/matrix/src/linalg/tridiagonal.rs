@@ -0,0 +1,307 @@
+use super::{Matrix, MatrixError};
+
+// Symmetric tridiagonal matrices are given by their diagonal d (length n)
+// and off-diagonal e (length n-1), where e[i] couples rows i and i+1.
+// n == 0 with both empty is allowed and has no eigenvalues.
+
+fn check_lengths(d: &Vec<f64>, e: &Vec<f64>, operation: &'static str) -> Result<(), MatrixError> {
+    if e.len() + 1 != d.len() && !(d.is_empty() && e.is_empty()) {
+        return Err(MatrixError::DimensionMismatch {operation: operation, lhs: (d.len(), 1), rhs: (e.len(), 1)})
+    }
+    Ok(())
+}
+
+fn sort_pairs(d: Vec<f64>, z: Matrix<f64>) -> (Vec<f64>, Matrix<f64>) {
+    let mut order: Vec<usize> = (0..d.len()).collect();
+    order.sort_by(|&i, &j| d[i].total_cmp(&d[j]));
+    let mut v = Matrix::zeros(z.num_rows, z.num_cols);
+    for (j, &k) in order.iter().enumerate() {
+        v[j].clone_from_slice(&z[k]);
+    }
+    return (order.iter().map(|&k| d[k]).collect(), v)
+}
+
+fn implicit_ql(d: &mut Vec<f64>, e: &Vec<f64>, mut z: Option<&mut Matrix<f64>>) -> Result<(), MatrixError> {
+    // d <- eigenvalues by implicit QL iteration with Wilkinson shifts, see Numerical Recipes tqli
+    // if given, the eigenvectors are accumulated into the columns of z
+    // fails with NoConvergence if eigenvalue l takes more than 60 iterations, e.g. for NaN elements
+    let n = d.len();
+    let mut e = e.clone();
+    e.push(0.0);
+    for l in 0..n {
+        let mut iter = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m+1].abs();
+                if e[m].abs() <= f64::EPSILON * dd {break}
+                m += 1;
+            }
+            if m == l {break}
+            iter += 1;
+            if iter > 60 {return Err(MatrixError::NoConvergence {index: l})}
+
+            let mut g = (d[l+1] - d[l]) / (2.0 * e[l]);
+            let mut r = g.hypot(1.0);
+            g = d[m] - d[l] + e[l] / (g + if g < 0.0 {-r} else {r});
+            let (mut s, mut c, mut p) = (1.0, 1.0, 0.0);
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i+1] = r;
+                if r == 0.0 {  // recover from underflow
+                    d[i+1] -= p;
+                    e[m] = 0.0;
+                    underflow = true;
+                    break
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i+1] - p;
+                r = (d[i] - g) * s + 2.0 * c * b;
+                p = s * r;
+                d[i+1] = g + p;
+                g = c * r - b;
+                if let Some(z) = z.as_mut() {
+                    for k in 0..n {
+                        let f = z[i+1][k];
+                        z[i+1][k] = s * z[i][k] + c * f;
+                        z[i][k] = c * z[i][k] - s * f;
+                    }
+                }
+            }
+            if underflow {continue}
+            d[l] -= p;
+            e[l] = g;
+            e[m] = 0.0;
+        }
+    }
+    return Ok(())
+}
+
+pub fn eigenvalues(d: &Vec<f64>, e: &Vec<f64>) -> Result<Vec<f64>, MatrixError> {
+    // all eigenvalues in ascending order, O(n^2)
+    check_lengths(d, e, "tridiagonal eigenvalues")?;
+    let mut eigvals = d.clone();
+    implicit_ql(&mut eigvals, e, None)?;
+    eigvals.sort_by(|a, b| a.total_cmp(b));
+    return Ok(eigvals)
+}
+
+pub fn sturm_count(d: &Vec<f64>, e: &Vec<f64>, x: f64) -> usize {
+    // number of eigenvalues smaller than x, from the signs of the LDL^T pivots of T - x I
+    check_lengths(d, e, "sturm_count").unwrap_or_else(|err| panic!("{}", err));
+    let mut count = 0;
+    let mut q = 1.0;
+    for i in 0..d.len() {
+        let e2 = if i == 0 {0.0} else {e[i-1] * e[i-1]};
+        q = d[i] - x - e2 / q;
+        if q == 0.0 {q = -f64::EPSILON * (x.abs() + f64::MIN_POSITIVE.sqrt())}
+        if q < 0.0 {count += 1}
+    }
+    return count
+}
+
+fn gershgorin(d: &Vec<f64>, e: &Vec<f64>) -> (f64, f64) {
+    let n = d.len();
+    let (mut lo, mut hi) = (f64::INFINITY, f64::NEG_INFINITY);
+    for i in 0..n {
+        let radius = if i > 0 {e[i-1].abs()} else {0.0} + if i + 1 < n {e[i].abs()} else {0.0};
+        lo = lo.min(d[i] - radius);
+        hi = hi.max(d[i] + radius);
+    }
+    return (lo, hi)
+}
+
+pub fn bisection(d: &Vec<f64>, e: &Vec<f64>, k: usize) -> Vec<f64> {
+    // the k lowest eigenvalues in ascending order by Sturm sequence bisection, O(n k)
+    check_lengths(d, e, "bisection").unwrap_or_else(|err| panic!("{}", err));
+    let (lo, hi) = gershgorin(d, e);
+    let mut eigvals = Vec::with_capacity(k);
+    for j in 0..usize::min(k, d.len()) {
+        // the j'th eigenvalue is the smallest x with more than j eigenvalues below it
+        let (mut a, mut b) = (eigvals.last().cloned().unwrap_or(lo), hi);
+        while b - a > 2.0 * f64::EPSILON * (a.abs() + b.abs()) + f64::MIN_POSITIVE {
+            let mid = 0.5 * (a + b);
+            if mid == a || mid == b {break}
+            if sturm_count(d, e, mid) > j {b = mid} else {a = mid}
+        }
+        eigvals.push(0.5 * (a + b));
+    }
+    return eigvals
+}
+
+fn solve_shifted(d: &Vec<f64>, e: &Vec<f64>, lambda: f64, b: &mut Vec<f64>) {
+    // b <- (T - lambda I)^-1 b by Gaussian elimination with partial pivoting
+    // a vanishing pivot is replaced by a tiny number as T - lambda I is nearly singular
+    let n = d.len();
+    let tiny = f64::EPSILON * (d.iter().fold(0.0, |max: f64, x| max.max(x.abs())) + e.iter().fold(0.0, |max: f64, x| max.max(x.abs())));
+    let tiny = if tiny == 0.0 {f64::EPSILON} else {tiny};
+    // row i of the upper triangle U has entries u0 (diagonal), u1, u2
+    let (mut u0, mut u1, mut u2) = (vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+    let mut row = [d[0] - lambda, if n > 1 {e[0]} else {0.0}, 0.0];
+    for i in 0..n {
+        if i + 1 == n {
+            u0[i] = if row[0] == 0.0 {tiny} else {row[0]};
+            break
+        }
+        let mut next = [e[i], d[i+1] - lambda, if i + 2 < n {e[i+1]} else {0.0}];
+        if next[0].abs() > row[0].abs() {
+            std::mem::swap(&mut row, &mut next);
+            b.swap(i, i+1);
+        }
+        if row[0] == 0.0 {row[0] = tiny}
+        let m = next[0] / row[0];
+        (u0[i], u1[i], u2[i]) = (row[0], row[1], row[2]);
+        b[i+1] -= m * b[i];
+        row = [next[1] - m * row[1], next[2] - m * row[2], 0.0];
+    }
+    for i in (0..n).rev() {
+        let mut sum = b[i];
+        if i + 1 < n {sum -= u1[i] * b[i+1]}
+        if i + 2 < n {sum -= u2[i] * b[i+2]}
+        b[i] = sum / u0[i];
+    }
+}
+
+fn orthonormalise(x: &mut Vec<f64>, basis: &[&[f64]]) {
+    for v in basis {
+        let dot: f64 = x.iter().zip(v.iter()).map(|(a, b)| a*b).sum();
+        for (xr, vr) in x.iter_mut().zip(v.iter()) {*xr -= dot * vr}
+    }
+    let norm = f64::sqrt(x.iter().map(|xi| xi*xi).sum());
+    for xi in x.iter_mut() {*xi /= norm}
+}
+
+fn iterate(d: &Vec<f64>, e: &Vec<f64>, lambda: f64, seed: usize, basis: &[&[f64]]) -> Vec<f64> {
+    // inverse iteration kept orthogonal to the eigenvectors of nearby eigenvalues in basis
+    let n = d.len();
+    let mut x: Vec<f64> = (0..n).map(|i| 1.0 + 0.1 * (((i + seed) * 7919) % 13) as f64).collect();
+    orthonormalise(&mut x, basis);
+    for _ in 0..3 {
+        solve_shifted(d, e, lambda, &mut x);
+        orthonormalise(&mut x, basis);
+    }
+    return x
+}
+
+pub fn inverse_iteration(d: &Vec<f64>, e: &Vec<f64>, lambda: f64) -> Vec<f64> {
+    // normalised eigenvector belonging to an accurate eigenvalue lambda
+    check_lengths(d, e, "inverse_iteration").unwrap_or_else(|err| panic!("{}", err));
+    iterate(d, e, lambda, 0, &[])
+}
+
+pub fn eig(d: &Vec<f64>, e: &Vec<f64>, k: Option<usize>) -> Result<(Vec<f64>, Matrix<f64>), MatrixError> {
+    // eigenvalues in ascending order with matching eigenvector columns
+    // None gives all eigenpairs by implicit QL, O(n^3), which fails with NoConvergence like eigenvalues
+    // Some(k) gives the k lowest by bisection and inverse iteration, O(n k) plus reorthogonalisation within clusters, and does not fail
+    check_lengths(d, e, "tridiagonal eig")?;
+    let n = d.len();
+    match k {
+        None => {
+            let mut eigvals = d.clone();
+            let mut z = Matrix::idty(n);
+            implicit_ql(&mut eigvals, e, Some(&mut z))?;
+            return Ok(sort_pairs(eigvals, z))
+        },
+        Some(k) => {
+            let eigvals = bisection(d, e, k);
+            let (lo, hi) = gershgorin(d, e);
+            let cluster = 1e-3 * (hi - lo);  // eigenvalues closer than this get their vectors orthogonalised
+            let mut v = Matrix::zeros(n, eigvals.len());
+            let mut first = 0;  // start of the current cluster
+            for j in 0..eigvals.len() {
+                if j > 0 && eigvals[j] - eigvals[j-1] >= cluster {first = j}
+                let basis: Vec<&[f64]> = (first..j).map(|i| &v[i]).collect();
+                let x = iterate(d, e, eigvals[j], j, &basis);
+                v[j].clone_from_slice(&x);
+            }
+            return Ok((eigvals, v))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn laplacian(n: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        // -1, 2, -1 with eigenvalues 2 - 2 cos(k pi / (n + 1))
+        let exact = (1..=n).map(|k| 2.0 - 2.0 * f64::cos(k as f64 * std::f64::consts::PI / (n + 1) as f64)).collect();
+        return (vec![2.0; n], vec![-1.0; n-1], exact)
+    }
+
+    fn residual(d: &Vec<f64>, e: &Vec<f64>, lambda: f64, x: &[f64]) -> f64 {
+        let n = d.len();
+        (0..n).map(|i| {
+            let mut tx = d[i] * x[i];
+            if i > 0 {tx += e[i-1] * x[i-1]}
+            if i + 1 < n {tx += e[i] * x[i+1]}
+            (tx - lambda * x[i]).abs()
+        }).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_ql() {
+        let (d, e, exact) = laplacian(50);
+        let (eigvals, v) = eig(&d, &e, None).unwrap();
+        for k in 0..50 {
+            assert!((eigvals[k] - exact[k]).abs() < 1e-13);
+            assert!(residual(&d, &e, eigvals[k], &v[k]) < 1e-13);
+        }
+        assert!(
+            (v.transpose() * &v - Matrix::idty(50)).iter().fold(true, |acc, item| acc && item.abs() < 1e-13)
+        );
+        assert_eq!(eigenvalues(&d, &e), Ok(eigvals));
+    }
+
+    #[test]
+    fn test_lowest() {
+        let (d, e, exact) = laplacian(2000);
+        assert_eq!(sturm_count(&d, &e, exact[3] + 1e-9), 4);
+
+        let (eigvals, v) = eig(&d, &e, Some(4)).unwrap();
+        assert_eq!(v.num_cols, 4);
+        for k in 0..4 {
+            assert!((eigvals[k] - exact[k]).abs() < 1e-14);
+            assert!(residual(&d, &e, eigvals[k], &v[k]) < 1e-13);
+        }
+    }
+
+    #[test]
+    fn test_degenerate() {
+        // two decoupled copies of the same block give doubly degenerate eigenvalues
+        let d = vec![1.0, 3.0, 1.0, 3.0];
+        let e = vec![1.0, 0.0, 1.0];
+        let (eigvals, v) = eig(&d, &e, Some(2)).unwrap();
+        assert!((eigvals[0] - (2.0 - 2.0_f64.sqrt())).abs() < 1e-14);
+        assert!((eigvals[1] - eigvals[0]).abs() < 1e-14);
+        assert!(
+            (v.transpose() * &v - Matrix::idty(2)).iter().fold(true, |acc, item| acc && item.abs() < 1e-13)
+        );
+    }
+
+    #[test]
+    fn test_no_convergence() {
+        let (mut d, e, _) = laplacian(5);
+        d[2] = f64::NAN;
+        assert!(matches!(eigenvalues(&d, &e), Err(MatrixError::NoConvergence {..})));
+        assert!(eig(&d, &e, None).is_err());
+    }
+
+    #[test]
+    fn test_lengths() {
+        let (d, e, _) = laplacian(5);
+        let err = MatrixError::DimensionMismatch {operation: "tridiagonal eigenvalues", lhs: (5, 1), rhs: (3, 1)};
+        assert_eq!(eigenvalues(&d, &e[..3].to_vec()), Err(err));
+        assert!(matches!(eig(&d, &d, None), Err(MatrixError::DimensionMismatch {..})));
+        assert!(matches!(eig(&d, &vec![], Some(2)), Err(MatrixError::DimensionMismatch {..})));
+
+        assert_eq!(eigenvalues(&vec![], &vec![]), Ok(vec![]));
+        let (eigvals, v) = eig(&vec![], &vec![], None).unwrap();
+        assert!(eigvals.is_empty() && (v.num_rows, v.num_cols) == (0, 0));
+        assert_eq!(eig(&vec![], &vec![], Some(3)).unwrap().0, vec![]);
+    }
+}
@@ -0,0 +1,17 @@
+#[macro_export]
+macro_rules! matrix {
+    // row-major literal, matrix![1.0, 2.0; 3.0, 4.0] has rows (1, 2) and (3, 4)
+    ($($($x:expr),+);+ $(;)?) => {
+        $crate::Matrix::from_rows(vec![$(vec![$($x),+]),+])
+    };
+}
+
+#[macro_export]
+macro_rules! assert_matrix_close {
+    // the tolerance is a Tolerance or an f64 absolute tolerance, on failure the worst element is reported
+    ($left:expr, $right:expr, $tol:expr $(,)?) => {
+        if let Some(message) = $crate::matrix_close_message(&$left, &$right, $crate::Tolerance::from($tol)) {
+            panic!("assertion `left ≈ right` failed: {}", message);
+        }
+    };
+}
@@ -1,7 +1,18 @@
+use super::{MatrixError, ReadError};
+
+mod blocks;
+mod complex;
 mod constructors;
+mod elementwise;
+mod io;
 mod matrix_add;
 mod matrix_multiply;
+mod predicates;
+mod random;
 mod scalar_multiply;
+mod view;
+pub use self::view::{MatrixView, MatrixViewMut, AsMatrixView, AsMatrixViewMut};
+pub use self::random::RandomSource;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Matrix<T> {
@@ -11,29 +22,44 @@ pub struct Matrix<T> {
 }
 
 impl<T: Copy> Matrix<T> {
-    pub fn new(mut mat: Vec<Vec<T>>) -> Self {
+    pub fn try_new(mat: Vec<Vec<T>>) -> Result<Self, MatrixError> {
+        // columns of equal, non-zero length
         let num_cols = mat.len();
-        let num_rows = mat[0].len();
+        let num_rows = match mat.first() {
+            Some(col) if col.len() > 0 => col.len(),
+            _ => return Err(MatrixError::Empty),
+        };
         let mut data = Vec::with_capacity(num_cols * num_rows);
-        for col in mat.iter_mut() {
-            while col.len() > 0 {
-                data.push(col.remove(0));
+        for col in mat {
+            if col.len() != num_rows {
+                return Err(MatrixError::DimensionMismatch {operation: "new", lhs: (num_rows, 1), rhs: (col.len(), 1)})
             }
+            data.extend(col);
         }
-        Self {
+        Ok(Self {
             data: data,
             num_cols: num_cols,
             num_rows: num_rows,
-        }
+        })
     }
 
-    pub fn from_data(data: Vec<T>, num_rows: usize, num_cols: usize) -> Self {
-        if data.len() != num_cols * num_rows {panic!("Non-compatible dimensions!");}
-        Self {
+    pub fn new(mat: Vec<Vec<T>>) -> Self {
+        Self::try_new(mat).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_data(data: Vec<T>, num_rows: usize, num_cols: usize) -> Result<Self, MatrixError> {
+        if data.len() != num_cols * num_rows {
+            return Err(MatrixError::DimensionMismatch {operation: "from_data", lhs: (num_rows, num_cols), rhs: (data.len(), 1)})
+        }
+        Ok(Self {
             data: data, 
             num_cols: num_cols, 
             num_rows: num_rows,
-        }
+        })
+    }
+
+    pub fn from_data(data: Vec<T>, num_rows: usize, num_cols: usize) -> Self {
+        Self::try_from_data(data, num_rows, num_cols).unwrap_or_else(|err| panic!("{}", err))
     }
 
     pub fn get(&self, row_index: usize, col_index: usize) -> T {
@@ -142,4 +168,18 @@ mod tests {
             Matrix::from_data(vec![2, 4, 6], 3, 1)
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(Matrix::<i32>::try_new(vec![]), Err(MatrixError::Empty));
+        assert_eq!(
+            Matrix::try_new(vec![vec![1, 2], vec![3]]),
+            Err(MatrixError::DimensionMismatch {operation: "new", lhs: (2, 1), rhs: (1, 1)})
+        );
+        assert_eq!(
+            Matrix::try_from_data(vec![1, 2, 3], 2, 2),
+            Err(MatrixError::DimensionMismatch {operation: "from_data", lhs: (2, 2), rhs: (3, 1)})
+        );
+        assert_eq!(Matrix::try_new(vec![vec![1, 2], vec![3, 4]]), Ok(Matrix::from_data(vec![1, 2, 3, 4], 2, 2)));
+    }
+}
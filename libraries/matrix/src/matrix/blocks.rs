@@ -0,0 +1,170 @@
+use super::{Matrix, MatrixError};
+use std::ops::Mul;
+
+// Assembly of matrices from blocks. The try_ variants report the first pair of blocks whose shapes do not fit together.
+
+fn shape<T>(mat: &Matrix<T>) -> (usize, usize) {
+    (mat.num_rows, mat.num_cols)
+}
+
+fn hstack<T: Copy>(blocks: &[&Matrix<T>], operation: &'static str) -> Result<Matrix<T>, MatrixError> {
+    // side by side, the column-major data of the blocks simply follow each other
+    let first = match blocks.first() {Some(first) => first, None => return Err(MatrixError::Empty)};
+    let num_cols = blocks.iter().map(|b| b.num_cols).sum();
+    let mut data = Vec::with_capacity(first.num_rows * num_cols);
+    for block in blocks {
+        if block.num_rows != first.num_rows {
+            return Err(MatrixError::DimensionMismatch {operation: operation, lhs: shape(first), rhs: shape(block)})
+        }
+        data.extend_from_slice(&block.data);
+    }
+    return Ok(Matrix {data: data, num_cols: num_cols, num_rows: first.num_rows})
+}
+
+fn vstack<T: Copy>(blocks: &[&Matrix<T>], operation: &'static str) -> Result<Matrix<T>, MatrixError> {
+    // on top of each other, column j is the concatenation of column j of every block
+    let first = match blocks.first() {Some(first) => first, None => return Err(MatrixError::Empty)};
+    for block in blocks {
+        if block.num_cols != first.num_cols {
+            return Err(MatrixError::DimensionMismatch {operation: operation, lhs: shape(first), rhs: shape(block)})
+        }
+    }
+    let num_rows = blocks.iter().map(|b| b.num_rows).sum();
+    let mut data = Vec::with_capacity(num_rows * first.num_cols);
+    for j in 0..first.num_cols {
+        for block in blocks {
+            data.extend_from_slice(&block[j]);
+        }
+    }
+    return Ok(Matrix {data: data, num_cols: first.num_cols, num_rows: num_rows})
+}
+
+impl<T: Copy> Matrix<T> {
+    pub fn try_hstack(blocks: &[&Self]) -> Result<Self, MatrixError> {
+        hstack(blocks, "hstack")
+    }
+
+    pub fn hstack(blocks: &[&Self]) -> Self {
+        Self::try_hstack(blocks).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_vstack(blocks: &[&Self]) -> Result<Self, MatrixError> {
+        vstack(blocks, "vstack")
+    }
+
+    pub fn vstack(blocks: &[&Self]) -> Self {
+        Self::try_vstack(blocks).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_block(blocks: &[Vec<&Self>]) -> Result<Self, MatrixError> {
+        // blocks[i][j] is the block in block row i and block column j
+        // blocks in a block row share their number of rows, blocks in a block column their number of columns
+        let rows = blocks.iter().map(|row| hstack(row, "block")).collect::<Result<Vec<Self>, _>>()?;
+        if let Some(first) = blocks.first() {
+            for row in blocks.iter().skip(1) {
+                if row.len() != first.len() {
+                    return Err(MatrixError::DimensionMismatch {operation: "block", lhs: (1, first.len()), rhs: (1, row.len())})
+                }
+                for (a, b) in first.iter().zip(row.iter()) {
+                    if a.num_cols != b.num_cols {
+                        return Err(MatrixError::DimensionMismatch {operation: "block", lhs: shape(a), rhs: shape(b)})
+                    }
+                }
+            }
+        }
+        vstack(&rows.iter().collect::<Vec<&Self>>(), "block")
+    }
+
+    pub fn block(blocks: &[Vec<&Self>]) -> Self {
+        Self::try_block(blocks).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl<T: From<u8> + Copy> Matrix<T> {
+    pub fn block_diag(blocks: &[&Self]) -> Self {
+        let num_rows = blocks.iter().map(|b| b.num_rows).sum();
+        let num_cols = blocks.iter().map(|b| b.num_cols).sum();
+        let mut result = Self::zeros(num_rows, num_cols);
+        let (mut row, mut col) = (0, 0);
+        for block in blocks {
+            for j in 0..block.num_cols {
+                result[col + j][row..row + block.num_rows].copy_from_slice(&block[j]);
+            }
+            row += block.num_rows;
+            col += block.num_cols;
+        }
+        return result
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Matrix<T> {
+    pub fn kron(&self, other: &Self) -> Self {
+        // Kronecker product, the block in block row i and block column j is a_ij B
+        let (p, q) = (other.num_rows, other.num_cols);
+        let mut data = Vec::with_capacity(self.data.len() * other.data.len());
+        for j in 0..self.num_cols {
+            for l in 0..q {
+                for i in 0..self.num_rows {
+                    let aij = self[j][i];
+                    data.extend(other[l].iter().map(|&b| aij * b));
+                }
+            }
+        }
+        return Matrix {data: data, num_cols: self.num_cols * q, num_rows: self.num_rows * p}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack() {
+        let a = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        let b = Matrix::new(vec![vec![5.0, 6.0]]);
+        assert_eq!(Matrix::hstack(&[&a, &b]), Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]));
+        assert_eq!(Matrix::vstack(&[&a, &b.transpose()]), Matrix::new(vec![vec![1.0, 2.0, 5.0], vec![3.0, 4.0, 6.0]]));
+
+        // regularised least squares system [A; sqrt(lambda) I]
+        let augmented = Matrix::vstack(&[&a, &(Matrix::idty(2) * 0.5)]);
+        assert_eq!(augmented.transpose() * &augmented, a.transpose() * &a + Matrix::idty(2) * 0.25);
+
+        assert_eq!(Matrix::try_vstack(&[&a, &b]), Err(MatrixError::DimensionMismatch {operation: "vstack", lhs: (2, 2), rhs: (2, 1)}));
+        assert_eq!(Matrix::<f64>::try_hstack(&[]), Err(MatrixError::Empty));
+
+        let empty = Matrix::hstack(&[&Matrix::<f64>::zeros(0, 2), &Matrix::zeros(0, 2)]);
+        assert_eq!((empty.num_rows, empty.num_cols), (0, 4));
+    }
+
+    #[test]
+    fn test_block() {
+        let a = Matrix::new(vec![vec![1, 2], vec![3, 4]]);
+        let b = Matrix::new(vec![vec![5, 6]]);
+        let c = Matrix::new(vec![vec![7], vec![8]]);
+        let d = Matrix::new(vec![vec![9]]);
+        let expected = Matrix::new(vec![vec![1, 2, 7], vec![3, 4, 8], vec![5, 6, 9]]);
+        assert_eq!(Matrix::block(&[vec![&a, &b], vec![&c, &d]]), expected);
+        assert_eq!(
+            Matrix::try_block(&[vec![&a, &b], vec![&d, &c]]),
+            Err(MatrixError::DimensionMismatch {operation: "block", lhs: (2, 2), rhs: (1, 1)})
+        );
+
+        let diag = Matrix::block_diag(&[&a, &d]);
+        assert_eq!(diag, Matrix::new(vec![vec![1, 2, 0], vec![3, 4, 0], vec![0, 0, 9]]));
+    }
+
+    #[test]
+    fn test_kron() {
+        let a = Matrix::new(vec![vec![1, 3], vec![2, 4]]);  // [[1, 2], [3, 4]]
+        let b = Matrix::new(vec![vec![0, 1], vec![5, 0], vec![1, 1]]);  // [[0, 5, 1], [1, 0, 1]]
+        let k = a.kron(&b);
+        assert_eq!((k.num_rows, k.num_cols), (4, 6));
+        assert_eq!(k.row(0), Matrix::new(vec![vec![0, 5, 1, 0, 10, 2]]));
+        assert_eq!(k.row(3), Matrix::new(vec![vec![3, 0, 3, 4, 0, 4]]));
+
+        // mixed product property (A x B)(C x D) = AC x BD
+        let c = Matrix::new(vec![vec![1, 0], vec![2, 1]]);
+        let d = Matrix::new(vec![vec![1, 0, 2], vec![0, 1, 0], vec![1, 1, 1]]);
+        assert_eq!(a.kron(&b) * c.kron(&d), (&a * &c).kron(&(&b * &d)));
+    }
+}
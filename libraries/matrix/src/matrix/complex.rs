@@ -0,0 +1,74 @@
+use super::Matrix;
+use num_complex::Complex;
+
+impl Matrix<Complex<f64>> {
+    pub fn from_parts(re: &Matrix<f64>, im: &Matrix<f64>) -> Self {
+        assert!(re.num_rows == im.num_rows && re.num_cols == im.num_cols, "Non-compatible dimensions!");
+        let data = re.iter().zip(im.iter()).map(|(&x, &y)| Complex::new(x, y)).collect();
+        Self::from_data(data, re.num_rows, re.num_cols)
+    }
+
+    pub fn from_real(re: &Matrix<f64>) -> Self {
+        Self::from_data(re.iter().map(|&x| Complex::new(x, 0.0)).collect(), re.num_rows, re.num_cols)
+    }
+
+    pub fn complex_zeros(num_rows: usize, num_cols: usize) -> Self {
+        Self::from_data(vec![Complex::new(0.0, 0.0); num_rows * num_cols], num_rows, num_cols)
+    }
+
+    pub fn complex_idty(dim: usize) -> Self {
+        let mut result = Self::complex_zeros(dim, dim);
+        for i in 0..dim {result.data[(dim + 1) * i] = Complex::new(1.0, 0.0)}
+        return result
+    }
+
+    pub fn re(&self) -> Matrix<f64> {
+        Matrix::from_data(self.iter().map(|z| z.re).collect(), self.num_rows, self.num_cols)
+    }
+
+    pub fn im(&self) -> Matrix<f64> {
+        Matrix::from_data(self.iter().map(|z| z.im).collect(), self.num_rows, self.num_cols)
+    }
+
+    pub fn conj(&self) -> Self {
+        Self::from_data(self.iter().map(|z| z.conj()).collect(), self.num_rows, self.num_cols)
+    }
+
+    pub fn conj_transpose(&self) -> Self {
+        let mut data = Vec::with_capacity(self.num_cols * self.num_rows);
+        for i in 0..self.num_rows {
+            for j in 0..self.num_cols {
+                data.push(self.get(i, j).conj());
+            }
+        }
+        return Self::from_data(data, self.num_cols, self.num_rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conj_transpose() {
+        let re = Matrix::from_data(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+        let im = Matrix::from_data(vec![0.0, -1.0, 2.0, 0.0, 1.0, 1.0], 2, 3);
+        let z = Matrix::from_parts(&re, &im);
+        let zh = z.conj_transpose();
+        assert_eq!((zh.num_rows, zh.num_cols), (3, 2));
+        assert_eq!(zh.re(), re.transpose());
+        assert_eq!(zh.im(), -im.transpose());
+        assert_eq!(z.conj().conj(), z);
+
+        // Z^H Z is Hermitian with a real diagonal
+        let g = &zh * &z;
+        assert_eq!(g.conj_transpose(), g);
+        assert_eq!(Matrix::complex_idty(3) * &g, g);
+    }
+
+    #[test]
+    fn test_display() {
+        let z = Matrix::from_parts(&Matrix::idty(2), &Matrix::from_data(vec![0.0, -1.0, 1.0, 0.0], 2, 2));
+        assert_eq!(format!("{z}"), "[\n[+1.000+0.000i +0.000+1.000i ]\n[+0.000-1.000i +1.000+0.000i ]\n]");
+    }
+}
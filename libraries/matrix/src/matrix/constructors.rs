@@ -1,4 +1,6 @@
-use super::Matrix;
+use super::{Matrix, MatrixError};
+use num_traits::One;
+use std::ops::Mul;
 
 impl<T: From<u8> + Copy> Matrix<T> {
     pub fn zeros(num_rows: usize, num_cols: usize) -> Self {
@@ -20,6 +22,75 @@ impl<T: From<u8> + Copy> Matrix<T> {
     pub fn zeros_like(matrix: &Self) -> Self {
         Self::zeros(matrix.num_rows, matrix.num_cols)
     }
+
+    pub fn diag(diagonal: &[T]) -> Self {
+        let dim = diagonal.len();
+        let mut result = Self::zeros(dim, dim);
+        for (i, &x) in diagonal.iter().enumerate() {result.data[(dim + 1) * i] = x}
+        return result
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    pub fn from_fn(num_rows: usize, num_cols: usize, f: impl Fn(usize, usize) -> T) -> Self {
+        // element (i, j) is f(i, j)
+        let mut data = Vec::with_capacity(num_rows * num_cols);
+        for j in 0..num_cols {
+            for i in 0..num_rows {data.push(f(i, j))}
+        }
+        Self::from_data(data, num_rows, num_cols)
+    }
+
+    pub fn try_from_rows(rows: Vec<Vec<T>>) -> Result<Self, MatrixError> {
+        // rows of equal, non-zero length, the row-major counterpart of try_new
+        let num_rows = rows.len();
+        let num_cols = match rows.first() {
+            Some(row) if row.len() > 0 => row.len(),
+            _ => return Err(MatrixError::Empty),
+        };
+        for row in rows.iter() {
+            if row.len() != num_cols {
+                return Err(MatrixError::DimensionMismatch {operation: "from_rows", lhs: (1, num_cols), rhs: (1, row.len())})
+            }
+        }
+        Ok(Self::from_fn(num_rows, num_cols, |i, j| rows[i][j]))
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        Self::try_from_rows(rows).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn toeplitz(first_col: &[T], first_row: &[T]) -> Self {
+        // constant along diagonals, A[i][j] = first_col[i - j] below and first_row[j - i] above the diagonal
+        // first_row[0] is ignored in favour of first_col[0]
+        Self::from_fn(first_col.len(), first_row.len(), |i, j| if i >= j {first_col[i - j]} else {first_row[j - i]})
+    }
+
+    pub fn circulant(first_col: &[T]) -> Self {
+        // every column is the previous one shifted cyclically down by one
+        let n = first_col.len();
+        Self::from_fn(n, n, |i, j| first_col[(n + i - j) % n])
+    }
+}
+
+impl<T: One + Mul<Output = T> + Copy> Matrix<T> {
+    pub fn vandermonde(x: &[T], num_cols: usize) -> Self {
+        // A[i][j] = x_i^j for j < num_cols
+        let mut result = Self::from_fn(x.len(), num_cols, |_, _| T::one());
+        for j in 1..num_cols {
+            for i in 0..x.len() {
+                result.data[j * x.len() + i] = result.data[(j - 1) * x.len() + i] * x[i];
+            }
+        }
+        return result
+    }
+}
+
+impl Matrix<f64> {
+    pub fn hilbert(dim: usize) -> Self {
+        // A[i][j] = 1 / (i + j + 1), notoriously ill-conditioned
+        Self::from_fn(dim, dim, |i, j| 1.0 / (i + j + 1) as f64)
+    }
 }
 
 #[cfg(test)]
@@ -59,6 +130,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_fn() {
+        let mat = Matrix::from_fn(2, 3, |i, j| 10 * i + j);
+        assert_eq!(mat, Matrix::from_rows(vec![vec![0, 1, 2], vec![10, 11, 12]]));
+        assert_eq!(mat, matrix![0, 1, 2; 10, 11, 12]);
+        assert_eq!(Matrix::diag(&[1, 2]), matrix![1, 0; 0, 2]);
+        assert_eq!(
+            Matrix::try_from_rows(vec![vec![1, 2], vec![3]]),
+            Err(MatrixError::DimensionMismatch {operation: "from_rows", lhs: (1, 2), rhs: (1, 1)})
+        );
+    }
+
+    #[test]
+    fn test_special() {
+        assert_eq!(Matrix::hilbert(2), matrix![1.0, 0.5; 0.5, 1.0 / 3.0]);
+        assert_eq!(Matrix::vandermonde(&[2, 3], 3), matrix![1, 2, 4; 1, 3, 9]);
+        assert_eq!(Matrix::toeplitz(&[1, 2, 3], &[1, 4]), matrix![1, 4; 2, 1; 3, 2]);
+        assert_eq!(Matrix::circulant(&[1, 2, 3]), matrix![1, 3, 2; 2, 1, 3; 3, 2, 1]);
+    }
+
     #[test]
     fn test_ones_1() {
         let mat1: Matrix<f32> = Matrix::ones(2, 3);
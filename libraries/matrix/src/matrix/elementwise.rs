@@ -0,0 +1,185 @@
+use super::{Matrix, MatrixError};
+use num_traits::{Float, Signed, Zero};
+use std::ops::{Div, Mul};
+use std::iter::zip;
+
+// Element-wise maps, reductions and norms.
+// Reductions along rows or columns return one value per row or column respectively.
+
+impl<T: Copy> Matrix<T> {
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> Matrix<U> {
+        Matrix {data: self.data.iter().map(|&x| f(x)).collect(), num_cols: self.num_cols, num_rows: self.num_rows}
+    }
+
+    pub fn zip_with<U: Copy, V>(&self, other: &Matrix<U>, f: impl Fn(T, U) -> V) -> Matrix<V> {
+        assert!(self.num_rows == other.num_rows && self.num_cols == other.num_cols, "Non-compatible dimensions!");
+        let data = zip(self.data.iter(), other.data.iter()).map(|(&x, &y)| f(x, y)).collect();
+        Matrix {data: data, num_cols: self.num_cols, num_rows: self.num_rows}
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Matrix<T> {
+    pub fn hadamard(&self, other: &Self) -> Self {
+        self.zip_with(other, |x, y| x * y)
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Matrix<T> {
+    pub fn hadamard_div(&self, other: &Self) -> Self {
+        self.zip_with(other, |x, y| x / y)
+    }
+}
+
+impl<T: Zero + Copy> Matrix<T> {
+    pub fn sum(&self) -> T {
+        self.data.iter().fold(T::zero(), |sum, &x| sum + x)
+    }
+
+    pub fn col_sums(&self) -> Vec<T> {
+        (0..self.num_cols).map(|j| self[j].iter().fold(T::zero(), |sum, &x| sum + x)).collect()
+    }
+
+    pub fn row_sums(&self) -> Vec<T> {
+        let mut result = vec![T::zero(); self.num_rows];
+        for j in 0..self.num_cols {
+            for (sum, &x) in zip(result.iter_mut(), &self[j]) {*sum = *sum + x}
+        }
+        return result
+    }
+
+    pub fn trace(&self) -> T {
+        assert!(self.num_rows == self.num_cols, "Matrix is not square");
+        (0..self.num_rows).fold(T::zero(), |sum, i| sum + self[i][i])
+    }
+}
+
+impl<T: Zero + Mul<Output = T> + Copy> Matrix<T> {
+    pub fn dot(&self, other: &Self) -> T {
+        // sum of a_ij b_ij without complex conjugation, the usual dot product for vectors
+        assert!(self.num_rows == other.num_rows && self.num_cols == other.num_cols, "Non-compatible dimensions!");
+        zip(self.data.iter(), other.data.iter()).fold(T::zero(), |sum, (&x, &y)| sum + x * y)
+    }
+}
+
+fn is_nan<T: PartialOrd>(x: &T) -> bool {
+    x.partial_cmp(x).is_none()
+}
+
+fn extreme<T: PartialOrd>(elements: impl Iterator<Item = T>, replaces: impl Fn(&T, &T) -> bool) -> T {
+    // the element that no other replaces, NaN only if every element is NaN
+    elements.fold(None, |best, x| match best {
+        Some(best) if !replaces(&x, &best) && !is_nan(&best) => Some(best),
+        _ => Some(x),
+    }).unwrap_or_else(|| panic!("{}", MatrixError::Empty))
+}
+
+impl<T: PartialOrd + Copy> Matrix<T> {
+    // NaN elements are skipped unless every element is NaN, and a reduction over no elements panics
+
+    pub fn max(&self) -> T {
+        extreme(self.data.iter().cloned(), |x, max| x > max)
+    }
+
+    pub fn min(&self) -> T {
+        extreme(self.data.iter().cloned(), |x, min| x < min)
+    }
+
+    pub fn col_max(&self) -> Vec<T> {
+        (0..self.num_cols).map(|j| extreme(self[j].iter().cloned(), |x, max| x > max)).collect()
+    }
+
+    pub fn row_max(&self) -> Vec<T> {
+        (0..self.num_rows).map(|i| extreme((0..self.num_cols).map(|j| self[j][i]), |x, max| x > max)).collect()
+    }
+
+    pub fn col_min(&self) -> Vec<T> {
+        (0..self.num_cols).map(|j| extreme(self[j].iter().cloned(), |x, min| x < min)).collect()
+    }
+
+    pub fn row_min(&self) -> Vec<T> {
+        (0..self.num_rows).map(|i| extreme((0..self.num_cols).map(|j| self[j][i]), |x, min| x < min)).collect()
+    }
+}
+
+impl<T: Signed + PartialOrd + Copy> Matrix<T> {
+    pub fn norm1(&self) -> T {
+        // maximum absolute column sum
+        self.map(|x| x.abs()).col_sums().into_iter().fold(T::zero(), |max, x| if x > max {x} else {max})
+    }
+
+    pub fn norm_inf(&self) -> T {
+        // maximum absolute row sum
+        self.map(|x| x.abs()).row_sums().into_iter().fold(T::zero(), |max, x| if x > max {x} else {max})
+    }
+
+    pub fn max_norm(&self) -> T {
+        // largest absolute element
+        self.data.iter().fold(T::zero(), |max, &x| if x.abs() > max {x.abs()} else {max})
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    pub fn frobenius_norm(&self) -> T {
+        // scaled by the largest element to avoid overflow and underflow in the squares
+        let scale = self.data.iter().fold(T::zero(), |max, &x| max.max(x.abs()));
+        if scale == T::zero() || scale.is_infinite() {return scale}
+        scale * self.data.iter().fold(T::zero(), |sum, &x| sum + (x / scale).powi(2)).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        let a = Matrix::new(vec![vec![1.0, -2.0], vec![3.0, 4.0], vec![0.5, -6.0]]);
+        let b = Matrix::new(vec![vec![2.0, 2.0], vec![1.0, -1.0], vec![4.0, 3.0]]);
+        assert_eq!(a.map(|x| x as i32), Matrix::new(vec![vec![1, -2], vec![3, 4], vec![0, -6]]));
+        assert_eq!(a.zip_with(&b, |x, y| x > y), Matrix::new(vec![vec![false, false], vec![true, true], vec![false, false]]));
+        assert_eq!(a.hadamard(&b), Matrix::new(vec![vec![2.0, -4.0], vec![3.0, -4.0], vec![2.0, -18.0]]));
+        assert_eq!(a.hadamard(&b).hadamard_div(&b), a);
+    }
+
+    #[test]
+    fn test_reductions() {
+        // [[1, 3, 0], [-2, 4, 5]]
+        let a = Matrix::new(vec![vec![1, -2], vec![3, 4], vec![0, 5]]);
+        assert_eq!(a.sum(), 11);
+        assert_eq!(a.col_sums(), vec![-1, 7, 5]);
+        assert_eq!(a.row_sums(), vec![4, 7]);
+        assert_eq!((a.max(), a.min()), (5, -2));
+        assert_eq!((a.col_max(), a.col_min()), (vec![1, 4, 5], vec![-2, 3, 0]));
+        assert_eq!((a.row_max(), a.row_min()), (vec![3, 5], vec![0, -2]));
+        assert_eq!(a.dot(&a), 55);
+        assert_eq!(Matrix::new(vec![vec![1, 2], vec![3, 4]]).trace(), 5);
+
+        let b = Matrix::new(vec![vec![f64::NAN, 1.0], vec![-1.0, f64::NAN]]);
+        assert_eq!((b.max(), b.min()), (1.0, -1.0));
+        assert_eq!(b.row_max(), vec![-1.0, 1.0]);
+        assert!(Matrix::new(vec![vec![f64::NAN]]).max().is_nan());
+
+        // no rows or no columns
+        let empty = Matrix::<f64>::zeros(0, 3);
+        assert_eq!((empty.col_sums(), empty.row_sums(), empty.sum()), (vec![0.0; 3], vec![], 0.0));
+        assert_eq!((empty.norm1(), empty.norm_inf(), empty.row_max()), (0.0, 0.0, vec![]));
+        assert_eq!(Matrix::<f64>::zeros(2, 0).row_sums(), vec![0.0; 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix has no elements")]
+    fn test_empty_max() {
+        Matrix::<f64>::zeros(0, 3).col_max();
+    }
+
+    #[test]
+    fn test_norms() {
+        let a = Matrix::new(vec![vec![1.0, -2.0], vec![3.0, 4.0], vec![0.0, -6.0]]);
+        assert_eq!(a.norm1(), 7.0);
+        assert_eq!(a.norm_inf(), 12.0);
+        assert_eq!(a.max_norm(), 6.0);
+        assert!((a.frobenius_norm() - 66f64.sqrt()).abs() < 1e-15);
+        assert!((Matrix::new(vec![vec![3e200, 4e200]]).frobenius_norm() / 5e200 - 1.0).abs() < 1e-15);
+        assert_eq!(Matrix::new(vec![vec![3i64, -4]]).norm1(), 7);
+    }
+}
@@ -0,0 +1,262 @@
+use super::{Matrix, ReadError};
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+// Plain text tables (whitespace or comma separated) and the MatrixMarket exchange format.
+// Numbers are written with the shortest representation that parses back to the same f64.
+
+fn parse_error(line: usize, message: String) -> ReadError {
+    ReadError::Parse {line: line, message: message}
+}
+
+fn parse_field<T: FromStr>(field: &str, line: usize) -> Result<T, ReadError> {
+    field.parse::<T>().map_err(|_| parse_error(line, format!("could not interpret '{field}' as a number")))
+}
+
+fn split_fields(line: &str) -> Vec<&str> {
+    // a comma anywhere makes the line comma separated, otherwise fields are separated by whitespace
+    if line.contains(',') {
+        line.split(',').map(|field| field.trim()).collect()
+    } else {
+        line.split_whitespace().collect()
+    }
+}
+
+fn data_lines<'a>(reader: impl BufRead + 'a, comment: &'a str, skipped: usize) -> impl Iterator<Item=Result<(usize, String), ReadError>> + 'a {
+    // numbered lines with comments removed, skipping those that are left blank
+    // skipped counts the lines already consumed from the reader
+    reader.lines().enumerate().filter_map(move |(i, line)| {
+        let mut line = match line {
+            Ok(line) => line,
+            Err(err) => return Some(Err(ReadError::Io(err))),
+        };
+        if let Some(start) = line.find(comment) {line.truncate(start)}
+        if line.trim().is_empty() {None} else {Some(Ok((i + 1 + skipped, line)))}
+    })
+}
+
+impl Matrix<f64> {
+    pub fn read_table(reader: impl BufRead) -> Result<Self, ReadError> {
+        // one row per line, everything after '#' is a comment
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+        for line in data_lines(reader, "#", 0) {
+            let (line, text) = line?;
+            let row = split_fields(&text).into_iter().map(|field| parse_field(field, line)).collect::<Result<Vec<f64>, _>>()?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(parse_error(line, format!("expected {} columns, found {}", first.len(), row.len())))
+                }
+            }
+            rows.push(row);
+        }
+        if rows.is_empty() {  // there is no line to point at
+            return Err(ReadError::Format("table has no data".to_string()))
+        }
+
+        let (num_rows, num_cols) = (rows.len(), rows[0].len());
+        let mut result = Self::zeros(num_rows, num_cols);
+        for (i, row) in rows.into_iter().enumerate() {
+            for (j, x) in row.into_iter().enumerate() {
+                result[j][i] = x;
+            }
+        }
+        return Ok(result)
+    }
+
+    pub fn write_table(&self, writer: &mut impl Write, delimiter: &str) -> std::io::Result<()> {
+        for i in 0..self.num_rows {
+            let row: Vec<String> = (0..self.num_cols).map(|j| format!("{:?}", self[j][i])).collect();
+            writeln!(writer, "{}", row.join(delimiter))?;
+        }
+        Ok(())
+    }
+
+    pub fn read_matrix_market(mut reader: impl BufRead) -> Result<Self, ReadError> {
+        // array and coordinate formats with real, integer or pattern fields and general, symmetric or skew-symmetric storage
+        // repeated coordinate entries are summed
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.to_lowercase();
+        let header: Vec<&str> = header.split_whitespace().collect();
+        if header.len() != 5 || header[0] != "%%matrixmarket" || header[1] != "matrix" {
+            return Err(parse_error(1, "expected '%%MatrixMarket matrix <format> <field> <symmetry>'".to_string()))
+        }
+        let coordinate = match header[2] {
+            "coordinate" => true,
+            "array" => false,
+            format => return Err(parse_error(1, format!("unknown format '{format}'"))),
+        };
+        let pattern = match header[3] {
+            "real" | "double" | "integer" => false,
+            "pattern" if coordinate => true,
+            field => return Err(parse_error(1, format!("unsupported field '{field}'"))),
+        };
+        let sign = match header[4] {
+            "general" => None,
+            "symmetric" => Some(1.0),
+            "skew-symmetric" => Some(-1.0),
+            symmetry => return Err(parse_error(1, format!("unsupported symmetry '{symmetry}'"))),
+        };
+
+        let mut lines = data_lines(reader, "%", 1);
+        let (line, size) = match lines.next() {
+            Some(line) => line?,
+            None => return Err(parse_error(2, "missing size line".to_string())),
+        };
+        let size = size.split_whitespace().map(|field| parse_field(field, line)).collect::<Result<Vec<usize>, _>>()?;
+        if size.len() != if coordinate {3} else {2} {
+            return Err(parse_error(line, format!("expected {} sizes, found {}", if coordinate {3} else {2}, size.len())))
+        }
+        let (num_rows, num_cols) = (size[0], size[1]);
+        if sign.is_some() && num_rows != num_cols {
+            return Err(parse_error(line, format!("symmetric storage of a {num_rows} x {num_cols} matrix")))
+        }
+        let num_entries = if coordinate {
+            size[2]
+        } else if sign == Some(-1.0) {
+            num_cols * num_cols.saturating_sub(1) / 2
+        } else if sign.is_some() {
+            num_cols * (num_cols + 1) / 2
+        } else {
+            num_rows * num_cols
+        };
+
+        let mut result = Self::zeros(num_rows, num_cols);
+        let mut count = 0;
+        let mut last_line = line;
+        // position of the next array entry, column-major and only the stored triangle for symmetric storage
+        let (mut i, mut j) = (if sign == Some(-1.0) {1} else {0}, 0);
+        for entry in lines {
+            let (line, text) = entry?;
+            if count == num_entries {
+                return Err(parse_error(line, format!("more than the {num_entries} declared entries")))
+            }
+            let fields: Vec<&str> = text.split_whitespace().collect();
+            let (row, col, value) = if coordinate {
+                if fields.len() != if pattern {2} else {3} {
+                    return Err(parse_error(line, format!("expected {} fields, found {}", if pattern {2} else {3}, fields.len())))
+                }
+                let (row, col): (usize, usize) = (parse_field(fields[0], line)?, parse_field(fields[1], line)?);
+                if row == 0 || row > num_rows || col == 0 || col > num_cols {
+                    return Err(parse_error(line, format!("index ({row}, {col}) is outside the {num_rows} x {num_cols} matrix")))
+                }
+                if sign.is_some() && row < col {
+                    return Err(parse_error(line, format!("index ({row}, {col}) is above the diagonal of symmetric storage")))
+                }
+                (row - 1, col - 1, if pattern {1.0} else {parse_field(fields[2], line)?})
+            } else {
+                if fields.len() != 1 {
+                    return Err(parse_error(line, format!("expected 1 field, found {}", fields.len())))
+                }
+                let position = (i, j);
+                i += 1;
+                if i == num_rows {
+                    j += 1;
+                    i = match sign {Some(s) if s < 0.0 => j + 1, Some(_) => j, None => 0};
+                }
+                (position.0, position.1, parse_field(fields[0], line)?)
+            };
+            result[col][row] += value;
+            if let Some(s) = sign {
+                if row != col {result[row][col] += s * value}
+            }
+            count += 1;
+            last_line = line;
+        }
+        if count != num_entries {
+            return Err(parse_error(last_line, format!("expected {num_entries} entries, found {count}")))
+        }
+        return Ok(result)
+    }
+
+    pub fn write_matrix_market(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        // dense array format, column-major
+        writeln!(writer, "%%MatrixMarket matrix array real general")?;
+        writeln!(writer, "{} {}", self.num_rows, self.num_cols)?;
+        for x in self.iter() {
+            writeln!(writer, "{:?}", x)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_matrix_market_coordinate(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        // sparse coordinate format listing the non-zero elements column by column
+        let nnz = self.iter().filter(|&&x| x != 0.0).count();
+        writeln!(writer, "%%MatrixMarket matrix coordinate real general")?;
+        writeln!(writer, "{} {} {}", self.num_rows, self.num_cols, nnz)?;
+        for j in 0..self.num_cols {
+            for i in 0..self.num_rows {
+                if self[j][i] != 0.0 {
+                    writeln!(writer, "{} {} {:?}", i + 1, j + 1, self[j][i])?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let text = "# x y\n1 2.5\n\n-3\t4e-300  # trailing comment\n";
+        let a = Matrix::read_table(text.as_bytes()).unwrap();
+        assert_eq!(a, Matrix::new(vec![vec![1.0, -3.0], vec![2.5, 4e-300]]));
+        let csv = Matrix::read_table("1, 2.5\n-3,4e-300\n".as_bytes()).unwrap();
+        assert_eq!(csv, a);
+
+        // floats survive a round trip exactly
+        let b = Matrix::new(vec![vec![0.1, 1.0 / 3.0, -2e20], vec![std::f64::consts::PI, 5e-324, f64::MAX]]);
+        for delimiter in [" ", ","] {
+            let mut buffer = Vec::new();
+            b.write_table(&mut buffer, delimiter).unwrap();
+            assert_eq!(Matrix::read_table(&buffer[..]).unwrap(), b);
+        }
+
+        match Matrix::read_table("1 2\n# comment\n3 x\n".as_bytes()) {
+            Err(ReadError::Parse {line, ..}) => assert_eq!(line, 3),
+            result => panic!("unexpected {:?}", result),
+        }
+        match Matrix::read_table("1 2\n3\n".as_bytes()) {
+            Err(err) => assert_eq!(format!("{err}"), "line 2: expected 2 columns, found 1"),
+            result => panic!("unexpected {:?}", result),
+        }
+        for text in ["", "# only a comment
+
+"] {
+            match Matrix::read_table(text.as_bytes()) {
+                Err(err) => assert_eq!(format!("{err}"), "table has no data"),
+                result => panic!("unexpected {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_market() {
+        let a = Matrix::new(vec![vec![1.0, 0.0, -2.5], vec![0.0, 0.0, 1e-17], vec![4.0, 0.0, 0.0]]);
+        let mut array = Vec::new();
+        a.write_matrix_market(&mut array).unwrap();
+        assert_eq!(Matrix::read_matrix_market(&array[..]).unwrap(), a);
+        let mut coordinate = Vec::new();
+        a.write_matrix_market_coordinate(&mut coordinate).unwrap();
+        assert!(String::from_utf8(coordinate.clone()).unwrap().starts_with("%%MatrixMarket matrix coordinate real general\n3 3 4\n"));
+        assert_eq!(Matrix::read_matrix_market(&coordinate[..]).unwrap(), a);
+
+        let symmetric = "%%MatrixMarket matrix coordinate real symmetric\n% comment\n2 2 2\n1 1 3.0\n2 1 -1\n";
+        assert_eq!(Matrix::read_matrix_market(symmetric.as_bytes()).unwrap(), Matrix::new(vec![vec![3.0, -1.0], vec![-1.0, 0.0]]));
+        let skew = "%%MatrixMarket matrix array real skew-symmetric\n3 3\n1\n2\n3\n";
+        assert_eq!(Matrix::read_matrix_market(skew.as_bytes()).unwrap(), Matrix::new(vec![vec![0.0, 1.0, 2.0], vec![-1.0, 0.0, 3.0], vec![-2.0, -3.0, 0.0]]));
+
+        let out_of_range = "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n\n3 1 1.0\n";
+        match Matrix::read_matrix_market(out_of_range.as_bytes()) {
+            Err(err) => assert_eq!(format!("{err}"), "line 5: index (3, 1) is outside the 2 x 2 matrix"),
+            result => panic!("unexpected {:?}", result),
+        }
+        match Matrix::read_matrix_market("%%MatrixMarket matrix array complex general\n".as_bytes()) {
+            Err(ReadError::Parse {line, ..}) => assert_eq!(line, 1),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+}
@@ -1,33 +1,137 @@
-use super::Matrix;
+use super::{Matrix, MatrixError};
 use std::ops::{Add, Mul};
 use std::iter::Sum;
+use std::thread;
 
-impl<T: Copy + Sum + Mul<Output = T> + Add<Output = T>> Matrix<T> {
-    pub fn mat_mul(lhs: &Self, rhs: &Self) -> Self {
+// square blocks of this size from the factors stay in cache while they are used
+const BLOCK_SIZE: usize = 64;
+// products with fewer multiply-adds than this are not worth spawning threads for
+const PARALLEL_THRESHOLD: usize = 1 << 21;
+
+fn block_kernel<T: Copy + Mul<Output = T> + Add<Output = T>>(a: &Matrix<T>, b: &Matrix<T>, first_col: usize, c: &mut [T]) {
+    // c += A B[:, first_col..], where c holds the matching columns of the product in column-major order
+    let (m, n) = (a.num_rows, a.num_cols);
+    if m == 0 {return}
+    let num_cols = c.len() / m;
+    for kk in (0..n).step_by(BLOCK_SIZE) {
+        for ii in (0..m).step_by(BLOCK_SIZE) {
+            let (k_end, i_end) = (usize::min(kk + BLOCK_SIZE, n), usize::min(ii + BLOCK_SIZE, m));
+            for j in 0..num_cols {
+                let c_col = &mut c[j * m + ii..j * m + i_end];
+                for k in kk..k_end {
+                    let bkj = b.data[(first_col + j) * n + k];
+                    let a_col = &a.data[k * m + ii..k * m + i_end];
+                    for (cij, &aik) in c_col.iter_mut().zip(a_col) {
+                        *cij = *cij + aik * bkj;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Copy + Sum + Mul<Output = T> + Add<Output = T> + Send + Sync> Matrix<T> {
+    fn check_product(lhs: &Self, rhs: &Self, operation: &'static str) -> Result<(), MatrixError> {
         if lhs.num_cols != rhs.num_rows {
-            panic!("Non-compatible dimensions!");
+            return Err(MatrixError::DimensionMismatch {
+                operation: operation, lhs: (lhs.num_rows, lhs.num_cols), rhs: (rhs.num_rows, rhs.num_cols)
+            })
         }
-        let mut data: Vec<T> = Vec::with_capacity(lhs.num_rows * rhs.num_cols);
-        for j in 0..rhs.num_cols {
-            let rhs_column_j = &rhs.data[j * rhs.num_rows .. (j + 1) * rhs.num_rows];
-            for i in 0..lhs.num_rows {
-                data.push(
-                    rhs_column_j.iter().enumerate().map(|(k, element)| *element * lhs.get(i, k)).sum()
-                );
-            }
+        Ok(())
+    }
+
+    fn accumulate_product(lhs: &Self, rhs: &Self, c: &mut [T], num_threads: usize) {
+        // c += lhs rhs, with the columns of c split evenly over the threads
+        let (m, num_threads) = (lhs.num_rows, num_threads.max(1));
+        let cols_per_thread = (rhs.num_cols + num_threads - 1) / num_threads;
+        if num_threads <= 1 || m == 0 || cols_per_thread == 0 {
+            block_kernel(lhs, rhs, 0, c);
+            return
         }
+        thread::scope(|scope| {
+            for (t, chunk) in c.chunks_mut(cols_per_thread * m).enumerate() {
+                scope.spawn(move || block_kernel(lhs, rhs, t * cols_per_thread, chunk));
+            }
+        });
+    }
+
+    fn default_threads(lhs: &Self, rhs: &Self) -> usize {
+        if lhs.num_rows * lhs.num_cols * rhs.num_cols < PARALLEL_THRESHOLD {return 1}
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+
+    pub fn try_mat_mul(lhs: &Self, rhs: &Self) -> Result<Self, MatrixError> {
+        Self::check_product(lhs, rhs, "mat_mul")?;
+        Ok(Self::mat_mul_threaded(lhs, rhs, Self::default_threads(lhs, rhs)))
+    }
+
+    pub fn mat_mul(lhs: &Self, rhs: &Self) -> Self {
+        Self::try_mat_mul(lhs, rhs).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn mat_mul_threaded(lhs: &Self, rhs: &Self, num_threads: usize) -> Self {
+        // blocked product on an explicit number of threads, mat_mul picks this from the size of the problem
+        Self::check_product(lhs, rhs, "mat_mul").unwrap_or_else(|err| panic!("{}", err));
+        let zero: T = std::iter::empty::<T>().sum();
+        let mut data = vec![zero; lhs.num_rows * rhs.num_cols];
+        Self::accumulate_product(lhs, rhs, &mut data, num_threads);
         return Matrix::from_data(data, lhs.num_rows, rhs.num_cols);
     }
 
     pub fn pow(matrix: &Self, power: u32) -> Self {
-        if power == 1 {return matrix.clone()}
-        matrix * Self::pow(matrix, power - 1)
+        // exponentiation by squaring, O(log power) products
+        assert!(power > 0, "Matrix power must be positive");
+        assert!(matrix.num_rows == matrix.num_cols, "Matrix is not square");
+        let mut base = matrix.clone();
+        let mut result: Option<Self> = None;
+        let mut power = power;
+        loop {
+            if power & 1 == 1 {
+                result = Some(match result {
+                    None => base.clone(),
+                    Some(result) => &result * &base,
+                });
+            }
+            power >>= 1;
+            if power == 0 {break}
+            base = &base * &base;
+        }
+        return result.unwrap()
+    }
+}
+
+impl<T: Copy + Sum + Mul<Output = T> + Add<Output = T> + Send + Sync + PartialEq> Matrix<T> {
+    pub fn try_gemm(alpha: T, a: &Self, b: &Self, beta: T, c: &mut Self) -> Result<(), MatrixError> {
+        // C <- alpha A B + beta C in place
+        Self::check_product(a, b, "gemm")?;
+        if c.num_rows != a.num_rows || c.num_cols != b.num_cols {
+            return Err(MatrixError::DimensionMismatch {
+                operation: "gemm", lhs: (a.num_rows, b.num_cols), rhs: (c.num_rows, c.num_cols)
+            })
+        }
+        // as in BLAS, beta = 0 overwrites C without reading it, so NaN or Inf in C do not propagate
+        let zero: T = std::iter::empty::<T>().sum();
+        for x in c.data.iter_mut() {*x = if beta == zero {zero} else {beta * *x}}
+        // scale the smaller of the two factors by alpha
+        let num_threads = Self::default_threads(a, b);
+        if a.data.len() <= b.data.len() {
+            let scaled = Matrix::from_data(a.data.iter().map(|&x| alpha * x).collect(), a.num_rows, a.num_cols);
+            Self::accumulate_product(&scaled, b, &mut c.data, num_threads);
+        } else {
+            let scaled = Matrix::from_data(b.data.iter().map(|&x| alpha * x).collect(), b.num_rows, b.num_cols);
+            Self::accumulate_product(a, &scaled, &mut c.data, num_threads);
+        }
+        return Ok(())
+    }
+
+    pub fn gemm(alpha: T, a: &Self, b: &Self, beta: T, c: &mut Self) {
+        Self::try_gemm(alpha, a, b, beta, c).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
 macro_rules! matrix_multiply {
     ($LHS:ty, $RHS:ty, $T:tt) => {
-        impl<$T: Add<Output = $T> + Mul<Output = $T> + Copy + Sum> Mul<$RHS> for $LHS {
+        impl<$T: Add<Output = $T> + Mul<Output = $T> + Copy + Sum + Send + Sync> Mul<$RHS> for $LHS {
             type Output = Matrix<$T>;
             fn mul(self, other: $RHS) -> Matrix<$T> {
                 Matrix::<$T>::mat_mul(&self, &other)
@@ -86,6 +190,53 @@ mod tests {
         let _ = mat2 * mat1;
     }
 
+    #[test]
+    fn test_try_mul() {
+        let mat1 = Matrix::new(vec![vec![1, 2], vec![1, 2], vec![1, 2]]);
+        let mat2 = Matrix::from_data((0..6).collect(), 2, 3);
+        assert_eq!(
+            Matrix::try_mat_mul(&mat2, &mat1),
+            Err(MatrixError::DimensionMismatch {operation: "mat_mul", lhs: (2, 3), rhs: (2, 3)})
+        );
+    }
+
+    #[test]
+    fn test_blocked() {
+        // sizes that are not multiples of the block size, compared to the naive triple loop
+        let (m, n, p) = (130, 70, 67);
+        let a = Matrix::from_data((0..m*n).map(|x| (x % 17) as i64 - 8).collect(), m, n);
+        let b = Matrix::from_data((0..n*p).map(|x| (x % 13) as i64 - 6).collect(), n, p);
+        let mut naive = Matrix::from_data(vec![0; m*p], m, p);
+        for i in 0..m {
+            for j in 0..p {
+                naive.set((0..n).map(|k| a.get(i, k) * b.get(k, j)).sum(), i, j);
+            }
+        }
+        assert_eq!(&a * &b, naive);
+        for num_threads in [2, 3, 8, 100] {
+            assert_eq!(Matrix::mat_mul_threaded(&a, &b, num_threads), naive);
+        }
+    }
+
+    #[test]
+    fn test_gemm() {
+        let a = Matrix::from_data((0..6).collect(), 2, 3);
+        let b = Matrix::from_data((0..12).collect(), 3, 4);
+        let mut c = Matrix::from_data(vec![1; 8], 2, 4);
+        Matrix::gemm(2, &a, &b, -1, &mut c);
+        assert_eq!(c, &a * &b * 2 - Matrix::from_data(vec![1; 8], 2, 4));
+        assert_eq!(
+            Matrix::try_gemm(1, &a, &b, 0, &mut Matrix::from_data(vec![0; 6], 2, 3)),
+            Err(MatrixError::DimensionMismatch {operation: "gemm", lhs: (2, 4), rhs: (2, 3)})
+        );
+
+        // beta = 0 ignores whatever C holds
+        let mut c = Matrix::from_data(vec![f64::NAN; 4], 2, 2);
+        let a = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        Matrix::gemm(1.0, &a, &a, 0.0, &mut c);
+        assert_eq!(c, &a * &a);
+    }
+
     #[test]
     fn test_pow_1() {
         let mat = Matrix::new(vec![vec![1, 3], vec![2, 4]]);
@@ -94,4 +245,15 @@ mod tests {
             (&mat * &mat) * &mat
         );
     }
+
+    #[test]
+    fn test_pow_2() {
+        // deep enough to overflow the stack with linear recursion
+        let mat = Matrix::new(vec![vec![1_i64, 0], vec![1, 1]]);
+        assert_eq!(
+            Matrix::pow(&mat, 1_000_000),
+            Matrix::new(vec![vec![1, 0], vec![1_000_000, 1]])
+        );
+        assert_eq!(Matrix::pow(&mat, 13), Matrix::new(vec![vec![1, 0], vec![13, 1]]));
+    }
 }
\ No newline at end of file
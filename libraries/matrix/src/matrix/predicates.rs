@@ -0,0 +1,82 @@
+use super::Matrix;
+use super::super::linalg::cholesky;
+
+// Structural predicates. Elements count as zero, and pairs of elements as equal, within the absolute tolerance tol.
+
+impl Matrix<f64> {
+    fn is_zero_where(&self, tol: f64, zero: impl Fn(usize, usize) -> bool) -> bool {
+        (0..self.num_cols).all(|j| (0..self.num_rows).all(|i| !zero(i, j) || self[j][i].abs() <= tol))
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.num_rows == self.num_cols
+    }
+
+    pub fn is_symmetric(&self, tol: f64) -> bool {
+        self.is_square() && (0..self.num_cols).all(|j| (0..j).all(|i| (self[j][i] - self[i][j]).abs() <= tol))
+    }
+
+    pub fn is_orthogonal(&self, tol: f64) -> bool {
+        // Q^T Q = I, for a square matrix this also gives Q Q^T = I
+        self.is_square() && (self.transpose() * self - Matrix::idty(self.num_cols)).max_norm() <= tol
+    }
+
+    pub fn is_diagonal(&self, tol: f64) -> bool {
+        self.is_zero_where(tol, |i, j| i != j)
+    }
+
+    pub fn is_upper_triangular(&self, tol: f64) -> bool {
+        self.is_zero_where(tol, |i, j| i > j)
+    }
+
+    pub fn is_lower_triangular(&self, tol: f64) -> bool {
+        self.is_zero_where(tol, |i, j| i < j)
+    }
+
+    pub fn is_upper_hessenberg(&self, tol: f64) -> bool {
+        self.is_square() && self.is_zero_where(tol, |i, j| i > j + 1)
+    }
+
+    pub fn is_positive_definite(&self, tol: f64) -> bool {
+        // symmetric within tol with every eigenvalue above tol, i.e. A - tol I has a Cholesky factorisation
+        if !self.is_symmetric(tol) {return false}
+        let mut shifted = self - Matrix::idty(self.num_rows) * tol;
+        return cholesky::decomp(&mut shifted).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structure() {
+        let a = Matrix::new(vec![vec![1.0, 2.0, 1e-12], vec![2.0, 5.0, 3.0], vec![0.0, 3.0, 6.0]]);
+        assert!(a.is_symmetric(1e-11) && !a.is_symmetric(1e-13));
+        assert!(a.is_upper_hessenberg(1e-11) && !a.is_upper_triangular(1e-11));
+        assert!(a.transpose().is_upper_hessenberg(1e-11));
+        assert!(Matrix::idty(3).is_diagonal(0.0) && !a.is_diagonal(0.1));
+        assert!(Matrix::new(vec![vec![1.0, 0.0], vec![2.0, 3.0], vec![4.0, 5.0]]).is_upper_triangular(0.0));
+        assert!(Matrix::new(vec![vec![1.0, 2.0], vec![0.0, 3.0]]).is_lower_triangular(0.0));
+        assert!(!Matrix::new(vec![vec![1.0, 2.0, 3.0]]).is_upper_hessenberg(0.0));
+    }
+
+    #[test]
+    fn test_orthogonal() {
+        let t: f64 = 0.3;
+        let rotation = Matrix::new(vec![vec![t.cos(), t.sin()], vec![-t.sin(), t.cos()]]);
+        assert!(rotation.is_orthogonal(1e-15));
+        assert!(!(rotation * 1.01).is_orthogonal(1e-3));
+        assert!(!Matrix::new(vec![vec![1.0, 0.0, 0.0]]).is_orthogonal(1e-15));
+    }
+
+    #[test]
+    fn test_positive_definite() {
+        // eigenvalues 2 - sqrt(2), 2 and 2 + sqrt(2)
+        let a = Matrix::new(vec![vec![2.0, -1.0, 0.0], vec![-1.0, 2.0, -1.0], vec![0.0, -1.0, 2.0]]);
+        assert!(a.is_positive_definite(0.5));
+        assert!(!a.is_positive_definite(0.6));
+        assert!(!(-a).is_positive_definite(0.0));
+        assert!(!Matrix::new(vec![vec![1.0, 0.0], vec![0.5, 1.0]]).is_positive_definite(0.0));
+    }
+}
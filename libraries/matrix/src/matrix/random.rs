@@ -0,0 +1,127 @@
+use super::Matrix;
+use super::super::linalg::qr;
+use num_complex::Complex;
+
+// Random matrices. The generator is anything implementing RandomSource, which scientific::rand::Rng does.
+
+pub trait RandomSource {
+    // uniformly distributed in [0, 1)
+    fn next_f64(&self) -> f64;
+}
+
+fn gaussian_pair(rng: &impl RandomSource) -> (f64, f64) {
+    // two independent standard normal numbers by the Box-Muller transform
+    let radius = (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt();
+    let angle = 2.0 * std::f64::consts::PI * rng.next_f64();
+    (radius * angle.cos(), radius * angle.sin())
+}
+
+fn gaussians(n: usize, rng: &impl RandomSource) -> Vec<f64> {
+    let mut result = Vec::with_capacity(n + 1);
+    while result.len() < n {
+        let (x, y) = gaussian_pair(rng);
+        result.push(x);
+        result.push(y);
+    }
+    result.truncate(n);
+    return result
+}
+
+impl Matrix<f64> {
+    pub fn random_uniform(num_rows: usize, num_cols: usize, rng: &impl RandomSource) -> Self {
+        // independent elements uniform in [0, 1)
+        Self::from_data((0..num_rows * num_cols).map(|_| rng.next_f64()).collect(), num_rows, num_cols)
+    }
+
+    pub fn random_gaussian(num_rows: usize, num_cols: usize, rng: &impl RandomSource) -> Self {
+        // independent standard normal elements
+        Self::from_data(gaussians(num_rows * num_cols, rng), num_rows, num_cols)
+    }
+
+    pub fn random_orthogonal(dim: usize, rng: &impl RandomSource) -> Self {
+        // Haar distributed, the Q of a Gaussian matrix with R normalised to a positive diagonal, see Mezzadri (2007)
+        // Gram-Schmidt already produces that normalisation
+        let mut q = Self::random_gaussian(dim, dim, rng);
+        let mut r = Self::zeros(dim, dim);
+        qr::decomp(&mut q, &mut r);
+        return q
+    }
+
+    pub fn random_spd(dim: usize, rng: &impl RandomSource) -> Self {
+        // G G^T + dim I for Gaussian G, symmetric with every eigenvalue at least dim
+        let g = Self::random_gaussian(dim, dim, rng);
+        let mut result = &g * g.transpose() + Self::idty(dim) * dim as f64;
+        for j in 0..dim {
+            for i in j+1..dim {result[j][i] = result[i][j]}
+        }
+        return result
+    }
+
+    pub fn random_goe(dim: usize, rng: &impl RandomSource) -> Self {
+        // Gaussian orthogonal ensemble (G + G^T) / 2, density proportional to exp(-tr H^2 / 2)
+        let g = Self::random_gaussian(dim, dim, rng);
+        return (&g + g.transpose()) * 0.5
+    }
+}
+
+impl Matrix<Complex<f64>> {
+    pub fn random_gue(dim: usize, rng: &impl RandomSource) -> Self {
+        // Gaussian unitary ensemble (Z + Z^H) / 2 for Z with standard normal real and imaginary parts,
+        // density proportional to exp(-tr H^2 / 2)
+        let z = Self::from_parts(&Matrix::random_gaussian(dim, dim, rng), &Matrix::random_gaussian(dim, dim, rng));
+        let mut result = (&z + z.conj_transpose()) * Complex::new(0.5, 0.0);
+        for i in 0..dim {result[i][i].im = 0.0}
+        return result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct Lcg(Cell<u64>);
+
+    impl RandomSource for Lcg {
+        fn next_f64(&self) -> f64 {
+            self.0.set(self.0.get().wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407));
+            (self.0.get() >> 11) as f64 / (1u64 << 53) as f64
+        }
+    }
+
+    fn max_diff(a: &Matrix<f64>, b: &Matrix<f64>) -> f64 {
+        (a - b).max_norm()
+    }
+
+    #[test]
+    fn test_distributions() {
+        let rng = Lcg(Cell::new(1));
+        let u = Matrix::random_uniform(100, 100, &rng);
+        assert!(u.min() >= 0.0 && u.max() < 1.0);
+        assert!((u.sum() / 1e4 - 0.5).abs() < 0.01);
+
+        let g = Matrix::random_gaussian(100, 100, &rng);
+        let mean = g.sum() / 1e4;
+        let variance = g.dot(&g) / 1e4 - mean * mean;
+        assert!(mean.abs() < 0.03 && (variance - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_ensembles() {
+        let rng = Lcg(Cell::new(7));
+        let q = Matrix::random_orthogonal(6, &rng);
+        assert!(max_diff(&(q.transpose() * &q), &Matrix::idty(6)) < 1e-14);
+
+        let s = Matrix::random_spd(6, &rng);
+        assert_eq!(s, s.transpose());
+        let mut l = s.clone();
+        assert!(super::super::super::linalg::cholesky::decomp(&mut l).is_ok());
+
+        let h = Matrix::random_goe(5, &rng);
+        assert_eq!(h, h.transpose());
+
+        let h = Matrix::random_gue(5, &rng);
+        assert_eq!(h, h.conj_transpose());
+        assert!(h.im().max_norm() > 0.0);
+    }
+}
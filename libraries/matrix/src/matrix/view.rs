@@ -0,0 +1,463 @@
+use super::Matrix;
+use std::ops::{Add, Sub, Mul, Neg, AddAssign, SubAssign, MulAssign, Index, IndexMut, Range};
+use std::iter::Sum;
+
+// Borrowed matrices with arbitrary strides, element (i, j) lives at data[i * row_stride + j * col_stride].
+// A Matrix is a view with row_stride 1 and col_stride num_rows.
+
+#[derive(Debug)]
+pub struct MatrixView<'a, T> {
+    data: &'a [T],
+    pub num_rows: usize,
+    pub num_cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+// derived Clone and Copy would require T: Copy
+impl<'a, T> Clone for MatrixView<'a, T> {
+    fn clone(&self) -> Self {*self}
+}
+
+impl<'a, T> Copy for MatrixView<'a, T> {}
+
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T> {
+    data: &'a mut [T],
+    pub num_rows: usize,
+    pub num_cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+fn offset(row_stride: usize, col_stride: usize, rows: &Range<usize>, cols: &Range<usize>) -> usize {
+    // index of the first element of a submatrix, or 0 if it is empty
+    if rows.is_empty() || cols.is_empty() {return 0}
+    rows.start * row_stride + cols.start * col_stride
+}
+
+macro_rules! view_common {
+    ($View:ident) => {
+        impl<'a, T: Copy> $View<'a, T> {
+            pub fn get(&self, row_index: usize, col_index: usize) -> T {
+                self[(row_index, col_index)]
+            }
+
+            pub fn is_contiguous(&self) -> bool {
+                // columns are contiguous slices of the underlying data
+                self.row_stride == 1 || self.num_rows <= 1
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item=&T> + '_ {
+                // column-major order like Matrix::iter
+                (0..self.num_cols).flat_map(move |j| (0..self.num_rows).map(move |i| &self[(i, j)]))
+            }
+
+            pub fn to_matrix(&self) -> Matrix<T> {
+                Matrix::from_data(self.iter().cloned().collect(), self.num_rows, self.num_cols)
+            }
+        }
+
+        impl<'a, T> Index<(usize, usize)> for $View<'a, T> {
+            type Output = T;
+
+            fn index(&self, (row_index, col_index): (usize, usize)) -> &T {
+                assert!(row_index < self.num_rows && col_index < self.num_cols, "Index out of bounds");
+                &self.data[row_index * self.row_stride + col_index * self.col_stride]
+            }
+        }
+    };
+}
+view_common!(MatrixView);
+view_common!(MatrixViewMut);
+
+impl<'a, T> MatrixView<'a, T> {
+    pub fn slice(&self, rows: Range<usize>, cols: Range<usize>) -> MatrixView<'a, T> {
+        assert!(rows.start <= rows.end && rows.end <= self.num_rows && cols.start <= cols.end && cols.end <= self.num_cols, "Index out of bounds");
+        MatrixView {
+            data: &self.data[offset(self.row_stride, self.col_stride, &rows, &cols)..],
+            num_rows: rows.len(),
+            num_cols: cols.len(),
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    pub fn row(&self, i: usize) -> MatrixView<'a, T> {
+        self.slice(i..i+1, 0..self.num_cols)
+    }
+
+    pub fn col(&self, j: usize) -> MatrixView<'a, T> {
+        self.slice(0..self.num_rows, j..j+1)
+    }
+
+    pub fn transpose(&self) -> MatrixView<'a, T> {
+        MatrixView {
+            data: self.data,
+            num_rows: self.num_cols,
+            num_cols: self.num_rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    pub fn diagonal(&self) -> MatrixView<'a, T> {
+        // the main diagonal as a column vector
+        let n = usize::min(self.num_rows, self.num_cols);
+        MatrixView {
+            data: if n == 0 {&self.data[..0]} else {self.data},
+            num_rows: n,
+            num_cols: 1,
+            row_stride: self.row_stride + self.col_stride,
+            col_stride: 0,
+        }
+    }
+}
+
+impl<'a, T> MatrixViewMut<'a, T> {
+    pub fn as_view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: &*self.data,
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    pub fn reborrow(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {
+            data: &mut *self.data,
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    pub fn set(&mut self, val: T, row_index: usize, col_index: usize) {
+        self[(row_index, col_index)] = val;
+    }
+
+    fn into_slice(self, rows: Range<usize>, cols: Range<usize>) -> MatrixViewMut<'a, T> {
+        assert!(rows.start <= rows.end && rows.end <= self.num_rows && cols.start <= cols.end && cols.end <= self.num_cols, "Index out of bounds");
+        MatrixViewMut {
+            data: &mut self.data[offset(self.row_stride, self.col_stride, &rows, &cols)..],
+            num_rows: rows.len(),
+            num_cols: cols.len(),
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    pub fn slice_mut(&mut self, rows: Range<usize>, cols: Range<usize>) -> MatrixViewMut<'_, T> {
+        self.reborrow().into_slice(rows, cols)
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+        let num_cols = self.num_cols;
+        self.slice_mut(i..i+1, 0..num_cols)
+    }
+
+    pub fn col_mut(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+        let num_rows = self.num_rows;
+        self.slice_mut(0..num_rows, j..j+1)
+    }
+
+    pub fn transpose_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {
+            data: &mut *self.data,
+            num_rows: self.num_cols,
+            num_cols: self.num_rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    pub fn diagonal_mut(&mut self) -> MatrixViewMut<'_, T> {
+        let n = usize::min(self.num_rows, self.num_cols);
+        MatrixViewMut {
+            data: if n == 0 {&mut self.data[..0]} else {&mut *self.data},
+            num_rows: n,
+            num_cols: 1,
+            row_stride: self.row_stride + self.col_stride,
+            col_stride: 0,
+        }
+    }
+}
+
+impl<'a, T: Copy> MatrixViewMut<'a, T> {
+    pub fn fill(&mut self, val: T) {
+        for j in 0..self.num_cols {
+            for i in 0..self.num_rows {
+                self[(i, j)] = val;
+            }
+        }
+    }
+
+    pub fn copy_from(&mut self, other: &impl AsMatrixView<T>) {
+        let other = other.as_view();
+        assert!(self.num_rows == other.num_rows && self.num_cols == other.num_cols, "Non-compatible dimensions!");
+        for j in 0..self.num_cols {
+            for i in 0..self.num_rows {
+                self[(i, j)] = other[(i, j)];
+            }
+        }
+    }
+}
+
+impl<'a, T> IndexMut<(usize, usize)> for MatrixViewMut<'a, T> {
+    fn index_mut(&mut self, (row_index, col_index): (usize, usize)) -> &mut T {
+        assert!(row_index < self.num_rows && col_index < self.num_cols, "Index out of bounds");
+        &mut self.data[row_index * self.row_stride + col_index * self.col_stride]
+    }
+}
+
+impl<T> Matrix<T> {
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: &self.data,
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
+            row_stride: 1,
+            col_stride: self.num_rows,
+        }
+    }
+
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
+            row_stride: 1,
+            col_stride: self.num_rows,
+            data: &mut self.data,
+        }
+    }
+
+    pub fn slice(&self, rows: Range<usize>, cols: Range<usize>) -> MatrixView<'_, T> {
+        self.view().slice(rows, cols)
+    }
+
+    pub fn slice_mut(&mut self, rows: Range<usize>, cols: Range<usize>) -> MatrixViewMut<'_, T> {
+        self.view_mut().into_slice(rows, cols)
+    }
+
+    pub fn diagonal(&self) -> MatrixView<'_, T> {
+        self.view().diagonal()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row_index, col_index): (usize, usize)) -> &T {
+        &self[col_index][row_index]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row_index, col_index): (usize, usize)) -> &mut T {
+        &mut self[col_index][row_index]
+    }
+}
+
+// Routines that only need element access take `&impl AsMatrixView<T>` or `&mut impl AsMatrixViewMut<T>`,
+// so that they accept owned matrices and views alike.
+pub trait AsMatrixView<T> {
+    fn as_view(&self) -> MatrixView<'_, T>;
+}
+
+pub trait AsMatrixViewMut<T>: AsMatrixView<T> {
+    fn as_view_mut(&mut self) -> MatrixViewMut<'_, T>;
+}
+
+impl<T> AsMatrixView<T> for Matrix<T> {
+    fn as_view(&self) -> MatrixView<'_, T> {self.view()}
+}
+
+impl<'a, T> AsMatrixView<T> for MatrixView<'a, T> {
+    fn as_view(&self) -> MatrixView<'_, T> {*self}
+}
+
+impl<'a, T> AsMatrixView<T> for MatrixViewMut<'a, T> {
+    fn as_view(&self) -> MatrixView<'_, T> {MatrixViewMut::as_view(self)}
+}
+
+impl<T> AsMatrixViewMut<T> for Matrix<T> {
+    fn as_view_mut(&mut self) -> MatrixViewMut<'_, T> {self.view_mut()}
+}
+
+impl<'a, T> AsMatrixViewMut<T> for MatrixViewMut<'a, T> {
+    fn as_view_mut(&mut self) -> MatrixViewMut<'_, T> {self.reborrow()}
+}
+
+impl<'a, 'b, T: Copy + PartialEq> PartialEq<MatrixView<'b, T>> for MatrixView<'a, T> {
+    fn eq(&self, other: &MatrixView<'b, T>) -> bool {
+        self.num_rows == other.num_rows && self.num_cols == other.num_cols && self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T: Copy + PartialEq> PartialEq<Matrix<T>> for MatrixView<'a, T> {
+    fn eq(&self, other: &Matrix<T>) -> bool {
+        *self == other.view()
+    }
+}
+
+fn zip_map<T: Copy>(lhs: MatrixView<T>, rhs: MatrixView<T>, f: impl Fn(T, T) -> T) -> Matrix<T> {
+    assert!(lhs.num_rows == rhs.num_rows && lhs.num_cols == rhs.num_cols, "Non-compatible dimensions!");
+    let data = lhs.iter().zip(rhs.iter()).map(|(a, b)| f(*a, *b)).collect();
+    Matrix::from_data(data, lhs.num_rows, lhs.num_cols)
+}
+
+impl<'a, T: Copy + Sum + Mul<Output = T> + Add<Output = T>> MatrixView<'a, T> {
+    pub fn mat_mul(lhs: MatrixView<T>, rhs: MatrixView<T>) -> Matrix<T> {
+        if lhs.num_cols != rhs.num_rows {
+            panic!("Non-compatible dimensions!");
+        }
+        let mut data: Vec<T> = Vec::with_capacity(lhs.num_rows * rhs.num_cols);
+        for j in 0..rhs.num_cols {
+            for i in 0..lhs.num_rows {
+                data.push((0..lhs.num_cols).map(|k| lhs[(i, k)] * rhs[(k, j)]).sum());
+            }
+        }
+        return Matrix::from_data(data, lhs.num_rows, rhs.num_cols);
+    }
+}
+
+// OPERATORS BETWEEN VIEWS AND MATRICES
+macro_rules! view_binary_ops {
+    ($LHS:ty, $RHS:ty) => {
+        impl<'a, 'b, T: Copy + Add<Output = T>> Add<$RHS> for $LHS {
+            type Output = Matrix<T>;
+            fn add(self, other: $RHS) -> Matrix<T> {
+                zip_map(self.as_view(), other.as_view(), |a, b| a + b)
+            }
+        }
+
+        impl<'a, 'b, T: Copy + Sub<Output = T>> Sub<$RHS> for $LHS {
+            type Output = Matrix<T>;
+            fn sub(self, other: $RHS) -> Matrix<T> {
+                zip_map(self.as_view(), other.as_view(), |a, b| a - b)
+            }
+        }
+
+        impl<'a, 'b, T: Copy + Sum + Mul<Output = T> + Add<Output = T>> Mul<$RHS> for $LHS {
+            type Output = Matrix<T>;
+            fn mul(self, other: $RHS) -> Matrix<T> {
+                MatrixView::mat_mul(self.as_view(), other.as_view())
+            }
+        }
+    };
+}
+view_binary_ops!(MatrixView<'a, T>, MatrixView<'b, T>);
+view_binary_ops!(MatrixView<'a, T>, &'b Matrix<T>);
+view_binary_ops!(MatrixView<'a, T>, Matrix<T>);
+view_binary_ops!(&'b Matrix<T>, MatrixView<'a, T>);
+view_binary_ops!(Matrix<T>, MatrixView<'a, T>);
+
+impl<'a, T: Copy + Mul<Output = T>> Mul<T> for MatrixView<'a, T> {
+    type Output = Matrix<T>;
+    fn mul(self, scalar: T) -> Matrix<T> {
+        let data = self.iter().map(|x| *x * scalar).collect();
+        Matrix::from_data(data, self.num_rows, self.num_cols)
+    }
+}
+
+impl<'a, T: Copy + Neg<Output = T>> Neg for MatrixView<'a, T> {
+    type Output = Matrix<T>;
+    fn neg(self) -> Matrix<T> {
+        let data = self.iter().map(|x| -*x).collect();
+        Matrix::from_data(data, self.num_rows, self.num_cols)
+    }
+}
+
+// IN-PLACE UPDATES OF MUTABLE VIEWS
+macro_rules! view_assign_ops {
+    ($Trait:ident, $method:ident, $op:tt) => {
+        impl<'a, 'r, T: Copy + $Trait, R: AsMatrixView<T>> $Trait<&'r R> for MatrixViewMut<'a, T> {
+            fn $method(&mut self, other: &R) {
+                let other = other.as_view();
+                assert!(self.num_rows == other.num_rows && self.num_cols == other.num_cols, "Non-compatible dimensions!");
+                for j in 0..self.num_cols {
+                    for i in 0..self.num_rows {
+                        self[(i, j)] $op other[(i, j)];
+                    }
+                }
+            }
+        }
+    };
+}
+view_assign_ops!(AddAssign, add_assign, +=);
+view_assign_ops!(SubAssign, sub_assign, -=);
+
+impl<'a, T: Copy + MulAssign> MulAssign<T> for MatrixViewMut<'a, T> {
+    fn mul_assign(&mut self, scalar: T) {
+        for j in 0..self.num_cols {
+            for i in 0..self.num_rows {
+                self[(i, j)] *= scalar;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counting(num_rows: usize, num_cols: usize) -> Matrix<i32> {
+        Matrix::from_data((0..(num_rows * num_cols) as i32).collect(), num_rows, num_cols)
+    }
+
+    #[test]
+    fn test_slice() {
+        let mat = counting(4, 3);
+        let sub = mat.slice(1..3, 1..3);
+        assert_eq!(sub, Matrix::from_data(vec![5, 6, 9, 10], 2, 2));
+        assert_eq!(sub.transpose(), Matrix::from_data(vec![5, 9, 6, 10], 2, 2));
+        assert_eq!(sub.row(1), Matrix::from_data(vec![6, 10], 1, 2));
+        assert_eq!(mat.view().col(2), mat.col(2));
+        assert_eq!(mat.diagonal(), Matrix::from_data(vec![0, 5, 10], 3, 1));
+        assert_eq!(mat.view().transpose().slice(0..2, 2..4).diagonal(), Matrix::from_data(vec![2, 7], 2, 1));
+        assert_eq!(mat.slice(4..4, 0..3).to_matrix(), Matrix::from_data(vec![], 0, 3));
+        assert_eq!(mat[(3, 1)], mat.get(3, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    fn test_slice_bounds() {
+        let mat = counting(4, 3);
+        let _ = mat.slice(1..3, 2..4);
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut mat = counting(3, 3);
+        mat.slice_mut(0..2, 1..3).fill(0);
+        assert_eq!(mat, Matrix::from_data(vec![0, 1, 2, 0, 0, 5, 0, 0, 8], 3, 3));
+
+        let mut view = mat.view_mut();
+        view.diagonal_mut().copy_from(&Matrix::from_data(vec![1, 1, 1], 3, 1));
+        view.transpose_mut().set(7, 0, 2);
+        let mut row = view.row_mut(2);
+        row *= 2;
+        assert_eq!(mat, Matrix::from_data(vec![1, 1, 14, 0, 1, 10, 0, 0, 2], 3, 3));
+    }
+
+    #[test]
+    fn test_view_ops() {
+        let mat = counting(3, 3);
+        let a = mat.slice(0..2, 0..2);
+        let b = mat.slice(1..3, 1..3);
+        assert_eq!(a + b, a.to_matrix() + b.to_matrix());
+        assert_eq!(b - &mat.slice(0..2, 0..2).to_matrix(), Matrix::from_data(vec![4; 4], 2, 2));
+        assert_eq!(a * b.transpose(), a.to_matrix() * b.transpose().to_matrix());
+        assert_eq!(&mat * mat.view().col(0), &mat * mat.col(0));
+        assert_eq!(-(a * 2), Matrix::from_data(vec![0, -2, -6, -8], 2, 2));
+
+        let mut sum = Matrix::zeros(2, 2);
+        let mut view = sum.view_mut();
+        view += &a;
+        view -= &b.transpose();
+        assert_eq!(sum, a - b.transpose());
+    }
+}
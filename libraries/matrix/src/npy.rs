@@ -0,0 +1,437 @@
+use super::{Matrix, ReadError};
+use num_complex::Complex;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+// NumPy .npy arrays and uncompressed .npz archives of them.
+// Matrices are written in Fortran order, which is exactly the column-major layout of Matrix::data.
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+pub trait NpyElement: Copy {
+    // NumPy type character and size in bytes, '<f8' is ('f', 8) in little endian
+    const KIND: char;
+    const SIZE: usize;
+    fn write_bytes(&self, out: &mut Vec<u8>);
+    fn read_bytes(bytes: &[u8], big_endian: bool) -> Self;
+}
+
+macro_rules! npy_element {
+    ($($T:ty, $kind:expr;)*) => {$(
+        impl NpyElement for $T {
+            const KIND: char = $kind;
+            const SIZE: usize = std::mem::size_of::<$T>();
+            fn write_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+            fn read_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                let mut buffer = [0; std::mem::size_of::<$T>()];
+                buffer.copy_from_slice(bytes);
+                if big_endian {<$T>::from_be_bytes(buffer)} else {<$T>::from_le_bytes(buffer)}
+            }
+        }
+    )*}
+}
+
+npy_element! {
+    f64, 'f'; f32, 'f';
+    i8, 'i'; i16, 'i'; i32, 'i'; i64, 'i';
+    u8, 'u'; u16, 'u'; u32, 'u'; u64, 'u';
+}
+
+macro_rules! npy_complex {
+    ($($T:ty),*) => {$(
+        impl NpyElement for Complex<$T> {
+            // real and imaginary parts stored consecutively
+            const KIND: char = 'c';
+            const SIZE: usize = 2 * <$T as NpyElement>::SIZE;
+            fn write_bytes(&self, out: &mut Vec<u8>) {
+                self.re.write_bytes(out);
+                self.im.write_bytes(out);
+            }
+            fn read_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                let half = <$T as NpyElement>::SIZE;
+                Complex::new(<$T>::read_bytes(&bytes[..half], big_endian), <$T>::read_bytes(&bytes[half..], big_endian))
+            }
+        }
+    )*}
+}
+
+npy_complex!(f32, f64);
+
+fn format_error(message: String) -> ReadError {
+    ReadError::Format(message)
+}
+
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, ReadError> {
+    // the text following 'key': in the header dictionary
+    let pattern = format!("'{key}':");
+    match header.find(&pattern) {
+        Some(start) => Ok(header[start + pattern.len()..].trim_start()),
+        None => Err(format_error(format!("npy header has no '{key}'"))),
+    }
+}
+
+fn parse_header(header: &str) -> Result<(String, bool, Vec<usize>), ReadError> {
+    // (descr, fortran_order, shape) from a header like {'descr': '<f8', 'fortran_order': True, 'shape': (3, 2), }
+    let descr = header_value(header, "descr")?;
+    let descr = match descr.get(1..).and_then(|rest| rest.find(|c| c == '\'' || c == '"')) {
+        Some(end) => descr[1..end + 1].to_string(),
+        None => return Err(format_error(format!("could not read descr in npy header {header}"))),
+    };
+    let fortran_order = header_value(header, "fortran_order")?.starts_with("True");
+    let shape = header_value(header, "shape")?;
+    let shape = match (shape.starts_with('('), shape.find(')')) {
+        (true, Some(end)) => &shape[1..end],
+        _ => return Err(format_error(format!("could not read shape in npy header {header}"))),
+    };
+    let shape = shape.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()).map(|x| {
+        x.parse::<usize>().map_err(|_| format_error(format!("could not interpret '{x}' as an array dimension")))
+    }).collect::<Result<Vec<usize>, _>>()?;
+    return Ok((descr, fortran_order, shape))
+}
+
+impl<T: NpyElement> Matrix<T> {
+    pub fn read_npy(mut reader: impl Read) -> Result<Self, ReadError> {
+        // one and two dimensional arrays in either order and byte order, a vector of length n becomes n x 1
+        let mut preamble = [0u8; 8];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..6] != MAGIC {
+            return Err(format_error("not an npy file".to_string()))
+        }
+        let header_len = match preamble[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as usize
+            },
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as usize
+            },
+            version => return Err(format_error(format!("unsupported npy version {version}"))),
+        };
+        let header = read_len(&mut reader, header_len)?;
+        let header = String::from_utf8(header).map_err(|_| format_error("npy header is not text".to_string()))?;
+        let (descr, fortran_order, shape) = parse_header(&header)?;
+
+        let expected = format!("{}{}", T::KIND, T::SIZE);
+        let big_endian = match (descr.get(..1), descr.get(1..)) {
+            (Some("<"), Some(rest)) | (Some("|"), Some(rest)) | (Some("="), Some(rest)) if rest == expected => false,
+            (Some(">"), Some(rest)) if rest == expected => true,
+            _ => return Err(format_error(format!("expected dtype '<{expected}', found '{descr}'"))),
+        };
+        let (num_rows, num_cols) = match shape[..] {
+            [] => (1, 1),
+            [n] => (n, 1),
+            [m, n] => (m, n),
+            _ => return Err(format_error(format!("cannot read a {}-dimensional array into a matrix", shape.len()))),
+        };
+
+        let len = num_rows.checked_mul(num_cols).and_then(|n| n.checked_mul(T::SIZE))
+            .ok_or_else(|| format_error(format!("npy shape {num_rows} x {num_cols} is too large")))?;
+        let bytes = read_len(&mut reader, len)?;
+        let data = bytes.chunks(T::SIZE).map(|x| T::read_bytes(x, big_endian)).collect();
+        if fortran_order || num_cols == 1 {
+            return Ok(Self::from_data(data, num_rows, num_cols))
+        }
+        return Ok(Self::from_data(data, num_cols, num_rows).transpose())
+    }
+
+    pub fn write_npy(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        // version 1.0 with the header padded so that the data starts on a multiple of 64 bytes
+        let mut header = format!(
+            "{{'descr': '{}{}{}', 'fortran_order': True, 'shape': ({}, {}), }}",
+            if T::SIZE == 1 {'|'} else {'<'}, T::KIND, T::SIZE, self.num_rows, self.num_cols
+        );
+        let padding = 63 - (MAGIC.len() + 4 + header.len()) % 64;
+        header.extend(std::iter::repeat(' ').take(padding));
+        header.push('\n');
+
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 4 + header.len() + self.data().len() * T::SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for x in self.iter() {
+            x.write_bytes(&mut bytes);
+        }
+        writer.write_all(&bytes)
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    // CRC-32 of zip archives, bitwise with the reflected polynomial 0xEDB88320
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {(crc >> 1) ^ 0xEDB88320} else {crc >> 1};
+        }
+    }
+    return !crc
+}
+
+fn read_len(reader: &mut impl Read, len: usize) -> Result<Vec<u8>, ReadError> {
+    // reads exactly len bytes without trusting len for the allocation
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(format_error("npy data is truncated".to_string()))
+    }
+    return Ok(bytes)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+struct NpzEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+fn zip_field<T: TryFrom<usize>>(value: usize, what: &str) -> std::io::Result<T> {
+    // sizes, offsets and counts of a zip archive without the zip64 extension are 16 or 32 bits wide
+    T::try_from(value).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{what} is too large for an npz archive")))
+}
+
+pub struct NpzWriter<W: Write> {
+    // uncompressed zip archive of name.npy entries, as written by numpy.savez
+    writer: W,
+    entries: Vec<NpzEntry>,
+    position: u32,
+}
+
+impl<W: Write> NpzWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {writer: writer, entries: Vec::new(), position: 0}
+    }
+
+    pub fn add<T: NpyElement>(&mut self, name: &str, mat: &Matrix<T>) -> std::io::Result<()> {
+        let mut data = Vec::new();
+        mat.write_npy(&mut data)?;
+        let name = format!("{name}.npy");
+        let name_len: u16 = zip_field(name.len(), "entry name")?;
+        let size = zip_field(data.len(), "entry")?;
+        let position = zip_field(self.position as usize + 30 + name.len() + data.len(), "archive")?;
+        let entry = NpzEntry {name: name, crc: crc32(&data), size: size, offset: self.position};
+
+        let mut header = Vec::with_capacity(30 + entry.name.len());
+        header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        header.extend_from_slice(&[20, 0, 0, 0, 0, 0]);  // version needed, flags, stored
+        header.extend_from_slice(&[0, 0, 0x21, 0]);  // time and date, 1980-01-01 00:00
+        header.extend_from_slice(&entry.crc.to_le_bytes());
+        header.extend_from_slice(&entry.size.to_le_bytes());
+        header.extend_from_slice(&entry.size.to_le_bytes());
+        header.extend_from_slice(&name_len.to_le_bytes());
+        header.extend_from_slice(&[0, 0]);
+        header.extend_from_slice(entry.name.as_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&data)?;
+        self.position = position;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> std::io::Result<W> {
+        // central directory and end of central directory record
+        let mut directory = Vec::new();
+        for entry in self.entries.iter() {
+            directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            directory.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0]);  // version made by and needed, flags, stored
+            directory.extend_from_slice(&[0, 0, 0x21, 0]);
+            directory.extend_from_slice(&entry.crc.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());  // checked in add
+            directory.extend_from_slice(&[0; 12]);  // extra field and comment lengths, disk, attributes
+            directory.extend_from_slice(&entry.offset.to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+        let count = zip_field::<u16>(self.entries.len(), "number of entries")?.to_le_bytes();
+        let directory_size = zip_field::<u32>(directory.len(), "central directory")?.to_le_bytes();
+        directory.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        directory.extend_from_slice(&[0, 0, 0, 0]);
+        directory.extend_from_slice(&count);
+        directory.extend_from_slice(&count);
+        directory.extend_from_slice(&directory_size);
+        directory.extend_from_slice(&self.position.to_le_bytes());
+        directory.extend_from_slice(&[0, 0]);
+        self.writer.write_all(&directory)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+pub struct NpzReader {
+    bytes: Vec<u8>,
+    // name without .npy, start of the data, size, checksum and whether it is stored uncompressed
+    entries: Vec<(String, usize, usize, u32, bool)>,
+}
+
+impl NpzReader {
+    pub fn new(mut reader: impl Read) -> Result<Self, ReadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let end = match (0..bytes.len().saturating_sub(21)).rev().find(|&i| read_u32(&bytes, i) == 0x06054b50) {
+            Some(end) => end,
+            None => return Err(format_error("not a zip archive".to_string())),
+        };
+        let count = read_u16(&bytes, end + 10) as usize;
+        let mut at = read_u32(&bytes, end + 16) as usize;
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            if at + 46 > bytes.len() || read_u32(&bytes, at) != 0x02014b50 {
+                return Err(format_error("corrupt zip central directory".to_string()))
+            }
+            let stored = read_u16(&bytes, at + 10) == 0;
+            let crc = read_u32(&bytes, at + 16);
+            let size = read_u32(&bytes, at + 20) as usize;
+            let name_len = read_u16(&bytes, at + 28) as usize;
+            let skip = name_len + read_u16(&bytes, at + 30) as usize + read_u16(&bytes, at + 32) as usize;
+            let offset = read_u32(&bytes, at + 42) as usize;
+            if at + 46 + name_len > bytes.len() {
+                return Err(format_error("corrupt zip central directory".to_string()))
+            }
+            let name = String::from_utf8_lossy(&bytes[at + 46..at + 46 + name_len]).into_owned();
+            if offset + 30 > bytes.len() || read_u32(&bytes, offset) != 0x04034b50 {
+                return Err(format_error(format!("corrupt zip entry '{name}'")))
+            }
+            let start = offset + 30 + read_u16(&bytes, offset + 26) as usize + read_u16(&bytes, offset + 28) as usize;
+            if start + size > bytes.len() {
+                return Err(format_error(format!("zip entry '{name}' is truncated")))
+            }
+            let name = name.trim_end_matches(".npy").to_string();
+            entries.push((name, start, size, crc, stored));
+            at += 46 + skip;
+        }
+        Ok(Self {bytes: bytes, entries: entries})
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|entry| &entry.0[..]).collect()
+    }
+
+    pub fn get<T: NpyElement>(&self, name: &str) -> Result<Matrix<T>, ReadError> {
+        let &(_, start, size, crc, stored) = match self.entries.iter().find(|entry| entry.0 == name) {
+            Some(entry) => entry,
+            None => return Err(format_error(format!("no array named '{name}' in the archive"))),
+        };
+        if !stored {
+            return Err(format_error(format!("array '{name}' is compressed, only uncompressed archives are supported")))
+        }
+        let data = &self.bytes[start..start + size];
+        if crc32(data) != crc {
+            return Err(format_error(format!("checksum of array '{name}' does not match")))
+        }
+        return Matrix::read_npy(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: NpyElement + PartialEq + std::fmt::Debug>(mat: &Matrix<T>) {
+        let mut bytes = Vec::new();
+        mat.write_npy(&mut bytes).unwrap();
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(bytes.len(), 10 + header_len + mat.data().len() * T::SIZE);
+        assert_eq!(&Matrix::<T>::read_npy(&bytes[..]).unwrap(), mat);
+    }
+
+    #[test]
+    fn test_npy() {
+        round_trip(&Matrix::new(vec![vec![0.1, 1.0 / 3.0], vec![-2e300, 5e-324], vec![f64::MAX, -0.0]]));
+        round_trip(&Matrix::new(vec![vec![1.5f32, -2.25], vec![3.0, 1e-40]]));
+        round_trip(&Matrix::new(vec![vec![i64::MIN, 2], vec![3, i64::MAX]]));
+        round_trip(&Matrix::new(vec![vec![255u8, 0, 7]]));
+        round_trip(&Matrix::new(vec![vec![Complex::new(1.0, -0.5), Complex::new(0.0, 3.0)]]));
+
+        let mut bytes = Vec::new();
+        Matrix::new(vec![vec![1.0, 2.0]]).write_npy(&mut bytes).unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("{'descr': '<f8', 'fortran_order': True, 'shape': (2, 1), }"));
+        match Matrix::<i32>::read_npy(&bytes[..]) {
+            Err(err) => assert_eq!(format!("{err}"), "expected dtype '<i4', found '<f8'"),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_npy_c_order() {
+        // numpy.save of np.array([[1, 2, 3], [4, 5, 6]], dtype='>i2') and of a vector
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        let header = "{'descr': '>i2', 'fortran_order': False, 'shape': (2, 3), }";
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for x in 1..=6i16 {bytes.extend_from_slice(&x.to_be_bytes())}
+        assert_eq!(Matrix::<i16>::read_npy(&bytes[..]).unwrap(), Matrix::new(vec![vec![1, 4], vec![2, 5], vec![3, 6]]));
+
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        let header = "{'descr': '<f4', 'fortran_order': False, 'shape': (3,), }";
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for x in [1.0f32, 2.0, 3.0] {bytes.extend_from_slice(&x.to_le_bytes())}
+        assert_eq!(Matrix::<f32>::read_npy(&bytes[..]).unwrap(), Matrix::new(vec![vec![1.0, 2.0, 3.0]]));
+    }
+
+    #[test]
+    fn test_npy_corrupt() {
+        for (shape, message) in [
+            ("(4294967296, 4294967296)", "npy shape 4294967296 x 4294967296 is too large"),
+            ("(1000000000, 1000000)", "npy data is truncated"),
+        ] {
+            let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+            let header = format!("{{'descr': '<f8', 'fortran_order': True, 'shape': {shape}, }}");
+            bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(header.as_bytes());
+            bytes.extend_from_slice(&[0u8; 16]);
+            match Matrix::<f64>::read_npy(&bytes[..]) {
+                Err(ReadError::Format(err)) => assert_eq!(err, message),
+                result => panic!("unexpected {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn test_npz() {
+        let params = Matrix::new(vec![vec![125.3, 4.1, 9.9]]);
+        let covariance = Matrix::new(vec![vec![0.1, 0.01, 0.0], vec![0.01, 0.2, -0.03], vec![0.0, -0.03, 0.3]]);
+        let counts = Matrix::new(vec![vec![3u32, 1, 4]]);
+        let mut archive = NpzWriter::new(Vec::new());
+        archive.add("params", &params).unwrap();
+        archive.add("covariance", &covariance).unwrap();
+        archive.add("counts", &counts).unwrap();
+        let bytes = archive.finish().unwrap();
+
+        let archive = NpzReader::new(&bytes[..]).unwrap();
+        assert_eq!(archive.names(), vec!["params", "covariance", "counts"]);
+        assert_eq!(archive.get::<f64>("covariance").unwrap(), covariance);
+        assert_eq!(archive.get::<f64>("params").unwrap(), params);
+        assert_eq!(archive.get::<u32>("counts").unwrap(), counts);
+        assert!(archive.get::<f64>("missing").is_err());
+
+        let mut corrupt = bytes.clone();
+        let directory = (0..corrupt.len()).find(|&i| read_u32(&corrupt, i) == 0x02014b50).unwrap();
+        corrupt[directory + 28..directory + 30].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert!(matches!(NpzReader::new(&corrupt[..]), Err(ReadError::Format(_))));
+
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+
+        let mut archive = NpzWriter::new(Vec::new());
+        let err = archive.add(&"x".repeat(70000), &params).unwrap_err();
+        assert_eq!(format!("{err}"), "entry name is too large for an npz archive");
+        assert!(zip_field::<u32>(u32::MAX as usize + 1, "entry").is_err());
+        assert!(NpzReader::new(&archive.finish().unwrap()[..]).unwrap().names().is_empty());
+    }
+}
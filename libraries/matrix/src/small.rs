@@ -0,0 +1,365 @@
+use super::{Matrix, MatrixError};
+use num_traits::{Float, Num, One, Zero};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Sub, Mul, Neg, AddAssign, SubAssign, MulAssign, Index, IndexMut};
+
+// Fixed-size matrices stored on the stack, for the many 2 x 2 and 3 x 3 operations where the heap allocation of Matrix dominates.
+// Storage is column-major like Matrix, m[j][i] is row i, column j.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SMatrix<T, const R: usize, const C: usize> {
+    data: [[T; R]; C],
+}
+
+pub type SVector<T, const N: usize> = SMatrix<T, N, 1>;
+
+impl<T: Copy, const R: usize, const C: usize> SMatrix<T, R, C> {
+    pub fn from_columns(columns: [[T; R]; C]) -> Self {
+        Self {data: columns}
+    }
+
+    pub fn from_rows(rows: [[T; C]; R]) -> Self {
+        Self::from_fn(|i, j| rows[i][j])
+    }
+
+    pub fn from_fn(f: impl Fn(usize, usize) -> T) -> Self {
+        Self {data: std::array::from_fn(|j| std::array::from_fn(|i| f(i, j)))}
+    }
+
+    pub fn transpose(&self) -> SMatrix<T, C, R> {
+        SMatrix::from_fn(|i, j| self.data[i][j])
+    }
+
+    pub fn map<U: Copy>(&self, f: impl Fn(T) -> U) -> SMatrix<U, R, C> {
+        SMatrix::from_fn(|i, j| f(self.data[j][i]))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&T> {
+        self.data.iter().flat_map(|col| col.iter())
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix::from_data(self.iter().cloned().collect(), R, C)
+    }
+}
+
+impl<T: Copy, const N: usize> SVector<T, N> {
+    pub fn new(elements: [T; N]) -> Self {
+        Self {data: [elements]}
+    }
+}
+
+impl<T: Zero + Copy, const R: usize, const C: usize> SMatrix<T, R, C> {
+    pub fn zeros() -> Self {
+        Self {data: [[T::zero(); R]; C]}
+    }
+}
+
+impl<T: Zero + Mul<Output = T> + Copy, const R: usize, const C: usize> SMatrix<T, R, C> {
+    pub fn dot(&self, other: &Self) -> T {
+        // sum of a_ij b_ij, the usual dot product for vectors
+        self.iter().zip(other.iter()).fold(T::zero(), |sum, (&x, &y)| sum + x * y)
+    }
+}
+
+impl<T: Zero + One + Copy, const N: usize> SMatrix<T, N, N> {
+    pub fn idty() -> Self {
+        Self::from_fn(|i, j| if i == j {T::one()} else {T::zero()})
+    }
+
+    pub fn trace(&self) -> T {
+        (0..N).fold(T::zero(), |sum, i| sum + self.data[i][i])
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> SMatrix<T, R, C> {
+    pub fn norm(&self) -> T {
+        // Euclidean norm for vectors, Frobenius norm for matrices
+        self.dot(self).sqrt()
+    }
+}
+
+impl<T: Num + Copy> SVector<T, 3> {
+    pub fn cross(&self, other: &Self) -> Self {
+        let (u, v) = (self.data[0], other.data[0]);
+        Self::new([u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]])
+    }
+}
+
+impl<T: Num + Copy> SVector<T, 2> {
+    pub fn cross(&self, other: &Self) -> T {
+        // z-component of the cross product of the vectors embedded in the plane
+        self.data[0][0] * other.data[0][1] - self.data[0][1] * other.data[0][0]
+    }
+}
+
+// closed-form determinants and inverses, the inverse is None for a vanishing determinant
+
+impl<T: Num + Copy> SMatrix<T, 1, 1> {
+    pub fn determinant(&self) -> T {
+        self.data[0][0]
+    }
+}
+
+impl<T: Num + Copy> SMatrix<T, 2, 2> {
+    pub fn determinant(&self) -> T {
+        let m = &self.data;
+        m[0][0] * m[1][1] - m[1][0] * m[0][1]
+    }
+}
+
+impl<T: Num + Copy> SMatrix<T, 3, 3> {
+    pub fn determinant(&self) -> T {
+        // expansion along the first column
+        let m = &self.data;
+        m[0][0] * (m[1][1] * m[2][2] - m[2][1] * m[1][2])
+            - m[0][1] * (m[1][0] * m[2][2] - m[2][0] * m[1][2])
+            + m[0][2] * (m[1][0] * m[2][1] - m[2][0] * m[1][1])
+    }
+}
+
+impl<T: Float> SMatrix<T, 1, 1> {
+    pub fn inverse(&self) -> Option<Self> {
+        if self.data[0][0] == T::zero() {return None}
+        Some(Self {data: [[T::one() / self.data[0][0]]]})
+    }
+}
+
+impl<T: Float> SMatrix<T, 2, 2> {
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == T::zero() {return None}
+        let m = &self.data;
+        Some(Self::from_columns([[m[1][1], -m[0][1]], [-m[1][0], m[0][0]]]) * (T::one() / det))
+    }
+}
+
+impl<T: Float> SMatrix<T, 3, 3> {
+    pub fn inverse(&self) -> Option<Self> {
+        // adjugate over determinant, the columns of the adjugate are cross products of the rows
+        let det = self.determinant();
+        if det == T::zero() {return None}
+        let rows = [self.row(0), self.row(1), self.row(2)];
+        let adjugate = Self::from_columns([
+            rows[1].cross(&rows[2]).data[0],
+            rows[2].cross(&rows[0]).data[0],
+            rows[0].cross(&rows[1]).data[0],
+        ]);
+        Some(adjugate * (T::one() / det))
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> SMatrix<T, R, C> {
+    pub fn row(&self, i: usize) -> SVector<T, C> {
+        // as a column vector, like Matrix::row
+        SVector::new(std::array::from_fn(|j| self.data[j][i]))
+    }
+
+    pub fn col(&self, j: usize) -> SVector<T, R> {
+        SVector::new(self.data[j])
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<usize> for SMatrix<T, R, C> {
+    type Output = [T; R];
+    fn index(&self, col_index: usize) -> &[T; R] {
+        &self.data[col_index]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<usize> for SMatrix<T, R, C> {
+    fn index_mut(&mut self, col_index: usize) -> &mut [T; R] {
+        &mut self.data[col_index]
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for SMatrix<T, R, C> {
+    type Output = T;
+    fn index(&self, (row_index, col_index): (usize, usize)) -> &T {
+        &self.data[col_index][row_index]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for SMatrix<T, R, C> {
+    fn index_mut(&mut self, (row_index, col_index): (usize, usize)) -> &mut T {
+        &mut self.data[col_index][row_index]
+    }
+}
+
+macro_rules! small_binary_op {
+    ($Trait:ident, $method:ident, $op:tt, $LHS:ty, $RHS:ty) => {
+        impl<T: $Trait<Output = T> + Copy, const R: usize, const C: usize> $Trait<$RHS> for $LHS {
+            type Output = SMatrix<T, R, C>;
+            fn $method(self, other: $RHS) -> SMatrix<T, R, C> {
+                SMatrix::from_fn(|i, j| self.data[j][i] $op other.data[j][i])
+            }
+        }
+    };
+}
+small_binary_op!(Add, add, +, SMatrix<T, R, C>, SMatrix<T, R, C>);
+small_binary_op!(Add, add, +, &SMatrix<T, R, C>, &SMatrix<T, R, C>);
+small_binary_op!(Add, add, +, SMatrix<T, R, C>, &SMatrix<T, R, C>);
+small_binary_op!(Add, add, +, &SMatrix<T, R, C>, SMatrix<T, R, C>);
+small_binary_op!(Sub, sub, -, SMatrix<T, R, C>, SMatrix<T, R, C>);
+small_binary_op!(Sub, sub, -, &SMatrix<T, R, C>, &SMatrix<T, R, C>);
+small_binary_op!(Sub, sub, -, SMatrix<T, R, C>, &SMatrix<T, R, C>);
+small_binary_op!(Sub, sub, -, &SMatrix<T, R, C>, SMatrix<T, R, C>);
+
+macro_rules! small_mat_mul {
+    ($LHS:ty, $RHS:ty) => {
+        impl<T: Zero + Mul<Output = T> + Copy, const R: usize, const K: usize, const C: usize> Mul<$RHS> for $LHS {
+            type Output = SMatrix<T, R, C>;
+            fn mul(self, other: $RHS) -> SMatrix<T, R, C> {
+                SMatrix::from_fn(|i, j| (0..K).fold(T::zero(), |sum, k| sum + self.data[k][i] * other.data[j][k]))
+            }
+        }
+    };
+}
+small_mat_mul!(SMatrix<T, R, K>, SMatrix<T, K, C>);
+small_mat_mul!(&SMatrix<T, R, K>, &SMatrix<T, K, C>);
+small_mat_mul!(SMatrix<T, R, K>, &SMatrix<T, K, C>);
+small_mat_mul!(&SMatrix<T, R, K>, SMatrix<T, K, C>);
+
+impl<T: Mul<Output = T> + Copy, const R: usize, const C: usize> Mul<T> for SMatrix<T, R, C> {
+    type Output = Self;
+    fn mul(self, scalar: T) -> Self {
+        self.map(|x| x * scalar)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy, const R: usize, const C: usize> Mul<T> for &SMatrix<T, R, C> {
+    type Output = SMatrix<T, R, C>;
+    fn mul(self, scalar: T) -> SMatrix<T, R, C> {
+        self.map(|x| x * scalar)
+    }
+}
+
+macro_rules! small_scalar_lhs {
+    ($($T:ty),*) => {$(
+        impl<const R: usize, const C: usize> Mul<SMatrix<$T, R, C>> for $T {
+            type Output = SMatrix<$T, R, C>;
+            fn mul(self, matrix: SMatrix<$T, R, C>) -> SMatrix<$T, R, C> {
+                matrix * self
+            }
+        }
+    )*}
+}
+small_scalar_lhs!(f64, f32, i32, i64);
+
+impl<T: Neg<Output = T> + Copy, const R: usize, const C: usize> Neg for SMatrix<T, R, C> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.map(|x| -x)
+    }
+}
+
+impl<T: AddAssign + Copy, const R: usize, const C: usize> AddAssign for SMatrix<T, R, C> {
+    fn add_assign(&mut self, other: Self) {
+        for j in 0..C {
+            for i in 0..R {self.data[j][i] += other.data[j][i]}
+        }
+    }
+}
+
+impl<T: SubAssign + Copy, const R: usize, const C: usize> SubAssign for SMatrix<T, R, C> {
+    fn sub_assign(&mut self, other: Self) {
+        for j in 0..C {
+            for i in 0..R {self.data[j][i] -= other.data[j][i]}
+        }
+    }
+}
+
+impl<T: MulAssign + Copy, const R: usize, const C: usize> MulAssign<T> for SMatrix<T, R, C> {
+    fn mul_assign(&mut self, scalar: T) {
+        for j in 0..C {
+            for i in 0..R {self.data[j][i] *= scalar}
+        }
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> From<SMatrix<T, R, C>> for Matrix<T> {
+    fn from(mat: SMatrix<T, R, C>) -> Self {
+        mat.to_matrix()
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> TryFrom<&Matrix<T>> for SMatrix<T, R, C> {
+    type Error = MatrixError;
+    fn try_from(mat: &Matrix<T>) -> Result<Self, MatrixError> {
+        if mat.num_rows != R || mat.num_cols != C {
+            return Err(MatrixError::DimensionMismatch {operation: "SMatrix::try_from", lhs: (R, C), rhs: (mat.num_rows, mat.num_cols)})
+        }
+        Ok(Self::from_fn(|i, j| mat[j][i]))
+    }
+}
+
+impl<T: fmt::Display + Copy, const R: usize, const C: usize> fmt::Display for SMatrix<T, R, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_matrix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ops() {
+        let a = SMatrix::from_rows([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = SMatrix::from_rows([[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+        assert_eq!(a[(1, 0)], 4.0);
+        assert_eq!(a[2], [3.0, 6.0]);
+        assert_eq!(a * b, SMatrix::from_rows([[4.0, 5.0], [10.0, 11.0]]));
+        assert_eq!(&a + &a, 2.0 * a);
+        assert_eq!(a - a, SMatrix::zeros());
+        assert_eq!(-a + a, SMatrix::zeros());
+        assert_eq!((a * b).to_matrix(), a.to_matrix() * b.to_matrix());
+        assert_eq!(a.transpose().transpose(), a);
+
+        let mut c = SMatrix::<i32, 2, 2>::idty();
+        c += SMatrix::from_rows([[1, 2], [3, 4]]);
+        c *= 2;
+        assert_eq!(c, SMatrix::from_rows([[4, 4], [6, 10]]));
+        assert_eq!(c.trace(), 14);
+    }
+
+    #[test]
+    fn test_vectors() {
+        let u = SVector::new([1.0, 2.0, 2.0]);
+        let v = SVector::new([0.0, 1.0, -1.0]);
+        assert_eq!(u.norm(), 3.0);
+        assert_eq!(u.dot(&v), 0.0);
+        let w = u.cross(&v);
+        assert_eq!(w, SVector::new([-4.0, 1.0, 1.0]));
+        assert_eq!((w.dot(&u), w.dot(&v)), (0.0, 0.0));
+        assert_eq!(SVector::new([1, 0]).cross(&SVector::new([0, 1])), 1);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = SMatrix::from_rows([[4.0, 7.0], [2.0, 6.0]]);
+        assert_eq!(a.determinant(), 10.0);
+        assert!((a * a.inverse().unwrap() - SMatrix::idty()).norm() < 1e-15);
+
+        let b = SMatrix::from_rows([[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]]);
+        assert_eq!(b.determinant(), 4.0);
+        let diff = b * b.inverse().unwrap() - SMatrix::idty();
+        assert!(diff.norm() < 1e-15);
+        assert_eq!(SMatrix::from_rows([[1.0, 2.0], [2.0, 4.0]]).inverse(), None);
+        assert_eq!(SMatrix::from_rows([[3]]).determinant(), 3);
+    }
+
+    #[test]
+    fn test_conversion() {
+        let a = Matrix::new(vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+        let s = SMatrix::<i32, 2, 3>::try_from(&a).unwrap();
+        assert_eq!(s[(1, 2)], 6);
+        assert_eq!(Matrix::from(s), a);
+        assert_eq!(
+            SMatrix::<i32, 3, 2>::try_from(&a),
+            Err(MatrixError::DimensionMismatch {operation: "SMatrix::try_from", lhs: (3, 2), rhs: (2, 3)})
+        );
+        assert_eq!(format!("{}", SVector::new([1.0, 2.0])), format!("{}", Matrix::new(vec![vec![1.0, 2.0]])));
+    }
+}
@@ -0,0 +1,424 @@
+use super::Matrix;
+use std::ops::{Add, Sub, Mul, Neg};
+
+// Compressed storage keeps for every major index (row for CSR, column for CSC) the range
+// ptr[k]..ptr[k+1] into the minor indices and values, with minor indices sorted and unique.
+// The CSR arrays of A are exactly the CSC arrays of A^T.
+
+fn transpose_compressed<T: Copy>(ptr: &Vec<usize>, idx: &Vec<usize>, vals: &Vec<T>, num_minor: usize) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    // counting sort on the minor index, keeps the new minor indices sorted
+    let mut new_ptr = vec![0; num_minor + 1];
+    for &i in idx {new_ptr[i + 1] += 1}
+    for i in 0..num_minor {new_ptr[i + 1] += new_ptr[i]}
+
+    let mut next = new_ptr.clone();
+    let mut new_idx = vec![0; idx.len()];
+    let mut new_vals = vals.clone();
+    for k in 0..ptr.len() - 1 {
+        for p in ptr[k]..ptr[k+1] {
+            let q = next[idx[p]];
+            new_idx[q] = k;
+            new_vals[q] = vals[p];
+            next[idx[p]] += 1;
+        }
+    }
+    return (new_ptr, new_idx, new_vals)
+}
+
+fn combine_compressed<T: Copy + From<u8>>(
+    lhs: (&Vec<usize>, &Vec<usize>, &Vec<T>), rhs: (&Vec<usize>, &Vec<usize>, &Vec<T>), op: impl Fn(T, T) -> T
+) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    // element-wise op of two compressed matrices of equal shape by merging the sorted minor indices
+    let zero = T::from(0);
+    let (mut ptr, mut idx, mut vals) = (vec![0], Vec::new(), Vec::new());
+    for k in 0..lhs.0.len() - 1 {
+        let (mut p, mut q) = (lhs.0[k], rhs.0[k]);
+        let (p_end, q_end) = (lhs.0[k+1], rhs.0[k+1]);
+        while p < p_end || q < q_end {
+            if q == q_end || (p < p_end && lhs.1[p] < rhs.1[q]) {
+                idx.push(lhs.1[p]);
+                vals.push(op(lhs.2[p], zero));
+                p += 1;
+            } else if p == p_end || rhs.1[q] < lhs.1[p] {
+                idx.push(rhs.1[q]);
+                vals.push(op(zero, rhs.2[q]));
+                q += 1;
+            } else {
+                idx.push(lhs.1[p]);
+                vals.push(op(lhs.2[p], rhs.2[q]));
+                p += 1;
+                q += 1;
+            }
+        }
+        ptr.push(idx.len());
+    }
+    return (ptr, idx, vals)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CooMatrix<T> {
+    // triplet builder, duplicate entries are summed on conversion
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    values: Vec<T>,
+    pub num_rows: usize,
+    pub num_cols: usize,
+}
+
+impl<T: Copy + From<u8> + Add<Output = T>> CooMatrix<T> {
+    pub fn new(num_rows: usize, num_cols: usize) -> Self {
+        Self {rows: Vec::new(), cols: Vec::new(), values: Vec::new(), num_rows: num_rows, num_cols: num_cols}
+    }
+
+    pub fn push(&mut self, val: T, row_index: usize, col_index: usize) {
+        assert!(row_index < self.num_rows && col_index < self.num_cols, "Index out of bounds");
+        self.rows.push(row_index);
+        self.cols.push(col_index);
+        self.values.push(val);
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        let mut order: Vec<usize> = (0..self.values.len()).collect();
+        order.sort_by_key(|&k| (self.rows[k], self.cols[k]));
+        let (mut row_ptr, mut col_indices, mut values) = (vec![0; self.num_rows + 1], Vec::<usize>::new(), Vec::<T>::new());
+        let mut last: Option<(usize, usize)> = None;
+        for k in order {
+            let (i, j) = (self.rows[k], self.cols[k]);
+            if last == Some((i, j)) {  // sum duplicates
+                let v = values.last_mut().unwrap();
+                *v = *v + self.values[k];
+            } else {
+                col_indices.push(j);
+                values.push(self.values[k]);
+                row_ptr[i + 1] += 1;
+                last = Some((i, j));
+            }
+        }
+        for i in 0..self.num_rows {row_ptr[i + 1] += row_ptr[i]}
+        CsrMatrix {row_ptr: row_ptr, col_indices: col_indices, values: values, num_rows: self.num_rows, num_cols: self.num_cols}
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        self.to_csr().to_csc()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T> {
+    row_ptr: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+    pub num_rows: usize,
+    pub num_cols: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T> {
+    col_ptr: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<T>,
+    pub num_rows: usize,
+    pub num_cols: usize,
+}
+
+impl<T: Copy + From<u8> + PartialEq> CsrMatrix<T> {
+    pub fn from_dense(mat: &Matrix<T>) -> Self {
+        let zero = T::from(0);
+        let (mut row_ptr, mut col_indices, mut values) = (vec![0], Vec::new(), Vec::new());
+        for i in 0..mat.num_rows {
+            for j in 0..mat.num_cols {
+                let val = mat.get(i, j);
+                if val != zero {
+                    col_indices.push(j);
+                    values.push(val);
+                }
+            }
+            row_ptr.push(values.len());
+        }
+        Self {row_ptr: row_ptr, col_indices: col_indices, values: values, num_rows: mat.num_rows, num_cols: mat.num_cols}
+    }
+}
+
+impl<T: Copy + From<u8>> CsrMatrix<T> {
+    pub fn to_dense(&self) -> Matrix<T> {
+        let mut result = Matrix::zeros(self.num_rows, self.num_cols);
+        for i in 0..self.num_rows {
+            for p in self.row_ptr[i]..self.row_ptr[i+1] {
+                result.set(self.values[p], i, self.col_indices[p]);
+            }
+        }
+        return result
+    }
+
+    pub fn get(&self, row_index: usize, col_index: usize) -> T {
+        let cols = &self.col_indices[self.row_ptr[row_index]..self.row_ptr[row_index+1]];
+        match cols.binary_search(&col_index) {
+            Ok(p) => self.values[self.row_ptr[row_index] + p],
+            Err(_) => T::from(0),
+        }
+    }
+}
+
+impl<T: Copy> CsrMatrix<T> {
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn row_ptr(&self) -> &Vec<usize> {&self.row_ptr}
+
+    pub fn col_indices(&self) -> &Vec<usize> {&self.col_indices}
+
+    pub fn values(&self) -> &Vec<T> {&self.values}
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        let (ptr, idx, vals) = transpose_compressed(&self.row_ptr, &self.col_indices, &self.values, self.num_cols);
+        CscMatrix {col_ptr: ptr, row_indices: idx, values: vals, num_rows: self.num_rows, num_cols: self.num_cols}
+    }
+
+    pub fn transpose(&self) -> Self {
+        let (ptr, idx, vals) = transpose_compressed(&self.row_ptr, &self.col_indices, &self.values, self.num_cols);
+        Self {row_ptr: ptr, col_indices: idx, values: vals, num_rows: self.num_cols, num_cols: self.num_rows}
+    }
+}
+
+impl<T: Copy + From<u8> + PartialEq> CscMatrix<T> {
+    pub fn from_dense(mat: &Matrix<T>) -> Self {
+        CsrMatrix::from_dense(mat).to_csc()
+    }
+}
+
+impl<T: Copy + From<u8>> CscMatrix<T> {
+    pub fn to_dense(&self) -> Matrix<T> {
+        let mut result = Matrix::zeros(self.num_rows, self.num_cols);
+        for j in 0..self.num_cols {
+            for p in self.col_ptr[j]..self.col_ptr[j+1] {
+                result[j][self.row_indices[p]] = self.values[p];
+            }
+        }
+        return result
+    }
+
+    pub fn get(&self, row_index: usize, col_index: usize) -> T {
+        let rows = &self.row_indices[self.col_ptr[col_index]..self.col_ptr[col_index+1]];
+        match rows.binary_search(&row_index) {
+            Ok(p) => self.values[self.col_ptr[col_index] + p],
+            Err(_) => T::from(0),
+        }
+    }
+}
+
+impl<T: Copy> CscMatrix<T> {
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn col_ptr(&self) -> &Vec<usize> {&self.col_ptr}
+
+    pub fn row_indices(&self) -> &Vec<usize> {&self.row_indices}
+
+    pub fn values(&self) -> &Vec<T> {&self.values}
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        let (ptr, idx, vals) = transpose_compressed(&self.col_ptr, &self.row_indices, &self.values, self.num_rows);
+        CsrMatrix {row_ptr: ptr, col_indices: idx, values: vals, num_rows: self.num_rows, num_cols: self.num_cols}
+    }
+
+    pub fn transpose(&self) -> Self {
+        let (ptr, idx, vals) = transpose_compressed(&self.col_ptr, &self.row_indices, &self.values, self.num_rows);
+        Self {col_ptr: ptr, row_indices: idx, values: vals, num_rows: self.num_cols, num_cols: self.num_rows}
+    }
+}
+
+impl<T: Copy + From<u8> + Add<Output = T> + Mul<Output = T>> CsrMatrix<T> {
+    pub fn mul_vec(&self, x: &Vec<T>) -> Vec<T> {
+        assert!(x.len() == self.num_cols, "Non-compatible dimensions!");
+        (0..self.num_rows).map(|i| {
+            (self.row_ptr[i]..self.row_ptr[i+1]).fold(T::from(0), |sum, p| sum + self.values[p] * x[self.col_indices[p]])
+        }).collect()
+    }
+
+    pub fn mat_mul(lhs: &Self, rhs: &Matrix<T>) -> Matrix<T> {
+        if lhs.num_cols != rhs.num_rows {
+            panic!("Non-compatible dimensions!");
+        }
+        let mut data: Vec<T> = Vec::with_capacity(lhs.num_rows * rhs.num_cols);
+        for j in 0..rhs.num_cols {
+            data.extend(lhs.mul_vec(&rhs[j].to_vec()));
+        }
+        return Matrix::from_data(data, lhs.num_rows, rhs.num_cols);
+    }
+}
+
+impl<T: Copy + From<u8> + Add<Output = T> + Mul<Output = T>> CscMatrix<T> {
+    pub fn mul_vec(&self, x: &Vec<T>) -> Vec<T> {
+        assert!(x.len() == self.num_cols, "Non-compatible dimensions!");
+        let mut y = vec![T::from(0); self.num_rows];
+        for j in 0..self.num_cols {
+            for p in self.col_ptr[j]..self.col_ptr[j+1] {
+                let i = self.row_indices[p];
+                y[i] = y[i] + self.values[p] * x[j];
+            }
+        }
+        return y
+    }
+
+    pub fn mat_mul(lhs: &Self, rhs: &Matrix<T>) -> Matrix<T> {
+        if lhs.num_cols != rhs.num_rows {
+            panic!("Non-compatible dimensions!");
+        }
+        let mut data: Vec<T> = Vec::with_capacity(lhs.num_rows * rhs.num_cols);
+        for j in 0..rhs.num_cols {
+            data.extend(lhs.mul_vec(&rhs[j].to_vec()));
+        }
+        return Matrix::from_data(data, lhs.num_rows, rhs.num_cols);
+    }
+}
+
+// SPARSE TIMES DENSE
+macro_rules! sparse_multiply_dense {
+    ($LHS:ty, $RHS:ty, $Sparse:ident, $T:tt) => {
+        impl<$T: Copy + From<u8> + Add<Output = $T> + Mul<Output = $T>> Mul<$RHS> for $LHS {
+            type Output = Matrix<$T>;
+            fn mul(self, other: $RHS) -> Matrix<$T> {
+                $Sparse::<$T>::mat_mul(&self, &other)
+            }
+        }
+    };
+}
+sparse_multiply_dense!(&CsrMatrix<T>, &Matrix<T>, CsrMatrix, T);
+sparse_multiply_dense!(CsrMatrix<T>, Matrix<T>, CsrMatrix, T);
+sparse_multiply_dense!(&CsrMatrix<T>, Matrix<T>, CsrMatrix, T);
+sparse_multiply_dense!(CsrMatrix<T>, &Matrix<T>, CsrMatrix, T);
+sparse_multiply_dense!(&CscMatrix<T>, &Matrix<T>, CscMatrix, T);
+sparse_multiply_dense!(CscMatrix<T>, Matrix<T>, CscMatrix, T);
+sparse_multiply_dense!(&CscMatrix<T>, Matrix<T>, CscMatrix, T);
+sparse_multiply_dense!(CscMatrix<T>, &Matrix<T>, CscMatrix, T);
+
+// ADD AND SUBTRACT TWO SPARSE MATRICES
+macro_rules! sparse_combine {
+    ($Trait:ident, $method:ident, $LHS:ty, $RHS:ty, $Sparse:ident, $ptr:ident, $idx:ident, $T:tt) => {
+        impl<$T: Copy + From<u8> + $Trait<Output = $T>> $Trait<$RHS> for $LHS {
+            type Output = $Sparse<$T>;
+            fn $method(self, other: $RHS) -> $Sparse<$T> {
+                if self.num_rows != other.num_rows || self.num_cols != other.num_cols {
+                    panic!("Non-compatible dimensions!");
+                }
+                let (ptr, idx, vals) = combine_compressed(
+                    (&self.$ptr, &self.$idx, &self.values), (&other.$ptr, &other.$idx, &other.values), |a, b| a.$method(b)
+                );
+                $Sparse {$ptr: ptr, $idx: idx, values: vals, num_rows: self.num_rows, num_cols: self.num_cols}
+            }
+        }
+    };
+}
+sparse_combine!(Add, add, &CsrMatrix<T>, &CsrMatrix<T>, CsrMatrix, row_ptr, col_indices, T);
+sparse_combine!(Add, add, CsrMatrix<T>, CsrMatrix<T>, CsrMatrix, row_ptr, col_indices, T);
+sparse_combine!(Sub, sub, &CsrMatrix<T>, &CsrMatrix<T>, CsrMatrix, row_ptr, col_indices, T);
+sparse_combine!(Sub, sub, CsrMatrix<T>, CsrMatrix<T>, CsrMatrix, row_ptr, col_indices, T);
+sparse_combine!(Add, add, &CscMatrix<T>, &CscMatrix<T>, CscMatrix, col_ptr, row_indices, T);
+sparse_combine!(Add, add, CscMatrix<T>, CscMatrix<T>, CscMatrix, col_ptr, row_indices, T);
+sparse_combine!(Sub, sub, &CscMatrix<T>, &CscMatrix<T>, CscMatrix, col_ptr, row_indices, T);
+sparse_combine!(Sub, sub, CscMatrix<T>, CscMatrix<T>, CscMatrix, col_ptr, row_indices, T);
+
+// SCALE AND NEGATE A SPARSE MATRIX
+macro_rules! sparse_scalar {
+    ($Sparse:ident, $T:tt) => {
+        impl<$T: Mul<Output = $T> + Copy> Mul<$T> for &$Sparse<$T> {
+            type Output = $Sparse<$T>;
+            fn mul(self, scalar: $T) -> $Sparse<$T> {
+                let mut result = self.clone();
+                for v in result.values.iter_mut() {*v = *v * scalar}
+                return result
+            }
+        }
+        impl<$T: Mul<Output = $T> + Copy> Mul<$T> for $Sparse<$T> {
+            type Output = $Sparse<$T>;
+            fn mul(self, scalar: $T) -> $Sparse<$T> {
+                &self * scalar
+            }
+        }
+        impl<$T: Neg<Output = $T> + Copy> Neg for &$Sparse<$T> {
+            type Output = $Sparse<$T>;
+            fn neg(self) -> $Sparse<$T> {
+                let mut result = self.clone();
+                for v in result.values.iter_mut() {*v = -*v}
+                return result
+            }
+        }
+        impl<$T: Neg<Output = $T> + Copy> Neg for $Sparse<$T> {
+            type Output = $Sparse<$T>;
+            fn neg(self) -> $Sparse<$T> {
+                -&self
+            }
+        }
+    };
+}
+sparse_scalar!(CsrMatrix, T);
+sparse_scalar!(CscMatrix, T);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> CooMatrix<i32> {
+        // [[1, 0, 2], [0, 0, 3], [4, 5, 0]] with the 2 split into a duplicate
+        let mut coo = CooMatrix::new(3, 3);
+        coo.push(5, 2, 1);
+        coo.push(1, 0, 0);
+        coo.push(3, 1, 2);
+        coo.push(1, 0, 2);
+        coo.push(4, 2, 0);
+        coo.push(1, 0, 2);
+        return coo
+    }
+
+    #[test]
+    fn test_conversion() {
+        let dense = Matrix::from_data(vec![1, 0, 4, 0, 0, 5, 2, 3, 0], 3, 3);
+        let csr = example().to_csr();
+        let csc = example().to_csc();
+        assert_eq!(csr.nnz(), 5);
+        assert_eq!(csr.to_dense(), dense);
+        assert_eq!(csc.to_dense(), dense);
+        assert_eq!(csr, CsrMatrix::from_dense(&dense));
+        assert_eq!(csc, CscMatrix::from_dense(&dense));
+        assert_eq!(csc.to_csr(), csr);
+        assert_eq!(csr.get(0, 2), 2);
+        assert_eq!(csc.get(1, 1), 0);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let csr = example().to_csr();
+        assert_eq!(csr.transpose().to_dense(), csr.to_dense().transpose());
+        assert_eq!(csr.to_csc().transpose().to_dense(), csr.to_dense().transpose());
+    }
+
+    #[test]
+    fn test_mul() {
+        let csr = example().to_csr();
+        let dense = csr.to_dense();
+        let b = Matrix::from_data((0..6).collect(), 3, 2);
+        assert_eq!(&csr * &b, &dense * &b);
+        assert_eq!(csr.to_csc() * &b, &dense * &b);
+        assert_eq!(csr.mul_vec(&vec![1, 1, 1]), vec![3, 3, 9]);
+        assert_eq!((&csr * 2).to_dense(), &dense * 2);
+    }
+
+    #[test]
+    fn test_add() {
+        let csr = example().to_csr();
+        let mut coo = CooMatrix::new(3, 3);
+        coo.push(-1, 0, 0);
+        coo.push(7, 1, 1);
+        let other = coo.to_csr();
+        assert_eq!((&csr + &other).to_dense(), csr.to_dense() + other.to_dense());
+        assert_eq!((&csr - &other).to_dense(), csr.to_dense() - other.to_dense());
+        assert_eq!((csr.to_csc() - other.to_csc()).to_dense(), csr.to_dense() - other.to_dense());
+        assert_eq!((-csr.clone()).to_dense(), -csr.to_dense());
+    }
+}
@@ -1,3 +1,5 @@
+extern crate matrix;
+
 pub mod spline;
 pub mod ode;
 pub mod integration;
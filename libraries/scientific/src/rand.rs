@@ -33,4 +33,10 @@ impl Rng {
         let f = std::f64::MANTISSA_DIGITS - 1;
         f64::from_bits((1 << (b - 2)) - (1 << f) + (self.u64() >> (b - f))) - 1.0
     }
-}
\ No newline at end of file
+}
+
+impl matrix::RandomSource for Rng {
+    fn next_f64(&self) -> f64 {
+        self.f64()
+    }
+}
@@ -1,21 +1,4 @@
-fn solve_tridiagonal_system(left_diag: &Vec<f64>, diag: &mut Vec<f64>, right_diag: &Vec<f64>, b: &mut Vec<f64>) {
-    // solve (left_diag)_i x_{i-1} + (diag)_i x_i + (right_diag)_i x_{i+1} = b_i
-    // all vectors must have same length n
-    // a[0] and q[n-1] are simply ignored!
-    
-    // simplified Gauss elemination for tri-diagonal systems
-    let n = left_diag.len();
-    for i in 1..n {
-        let w = left_diag[i] / diag[i-1];
-        diag[i] -= w * right_diag[i-1];
-        b[i] -= w * b[i-1];
-    }
-    // back substitution for bi-diagonal system
-    b[n-1] = b[n-1] / diag[n-1];
-    for i in (0..n-1).rev() {
-        b[i] = (b[i] - right_diag[i] * b[i+1]) / diag[i];
-    }
-}
+use matrix::TridiagonalMatrix;
 
 fn binary_search_bin<T: PartialOrd>(sorted_array: &Vec<T>, item: T) -> usize {
     if item < sorted_array[0] || item > *sorted_array.last().unwrap() {panic!("item is out of bounds")}
@@ -73,18 +56,17 @@ impl CubicSpline {
         //q.push(0.0);
         b.push(3.0 * dydx[n-2]);
 
-        let left_diag = vec![1.0; n]; // the left diagonal
-        solve_tridiagonal_system(&left_diag, &mut d, &q, &mut b); // b is now the right spline coefficient
+        let system = TridiagonalMatrix::new(vec![1.0; n-1], d, q);
+        system.solve(&mut b).expect("Spline system is singular"); // b is now the right spline coefficient
 
-        d.pop();
+        let mut c = Vec::<f64>::with_capacity(n-1);
+        let mut d = Vec::<f64>::with_capacity(n-1);
         for i in 0..n-1 {
-            // reuse q-vector as spline c coefficient
-            q[i] = (-2.0*b[i] - b[i+1] + 3.0 * dydx[i]) / dx[i];
-            // reuse d-vector as spline d coefficient
-            d[i] = (b[i] + b[i+1] - 2.0 * dydx[i]) / (dx[i] * dx[i]);
+            c.push((-2.0*b[i] - b[i+1] + 3.0 * dydx[i]) / dx[i]);
+            d.push((b[i] + b[i+1] - 2.0 * dydx[i]) / (dx[i] * dx[i]));
         }
 
-        return (b, q, d)
+        return (b, c, d)
     }
     
     pub fn evaluate(&self, z: f64) -> f64 {